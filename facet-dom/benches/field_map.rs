@@ -0,0 +1,146 @@
+//! Deserializing many elements of the same struct type.
+//!
+//! Run with `cargo bench -p facet-dom --features bench --bench field_map`.
+//!
+//! Both variants deserialize a feed with the given number of `<entry>` elements,
+//! each of which needs the field lookup map of `Entry`:
+//!
+//! - `entries` uses the field map cache, so the map is built once per type.
+//! - `entries_uncached` turns the cache off, so the map is built for every element.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+
+use divan::{AllocProfiler, Bencher, black_box};
+use facet::Facet;
+use facet_dom::{DomDeserializer, DomEvent, DomParser};
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+#[derive(Facet)]
+struct Feed {
+    title: String,
+    #[facet(rename = "entry")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Facet)]
+#[facet(rename_all = "kebab-case")]
+struct Entry {
+    #[facet(attribute)]
+    id: String,
+    title: String,
+    author_name: String,
+    updated_at: String,
+    summary: Option<String>,
+    #[facet(rename = "category")]
+    categories: Vec<String>,
+}
+
+/// Replays a prebuilt list of events.
+struct Events(VecDeque<DomEvent<'static>>);
+
+impl DomParser<'static> for Events {
+    type Error = Infallible;
+
+    fn next_event(&mut self) -> Result<Option<DomEvent<'static>>, Self::Error> {
+        Ok(self.0.pop_front())
+    }
+
+    fn peek_event(&mut self) -> Result<Option<&DomEvent<'static>>, Self::Error> {
+        Ok(self.0.front())
+    }
+
+    fn skip_node(&mut self) -> Result<(), Self::Error> {
+        let mut depth = 0usize;
+        while let Some(event) = self.0.pop_front() {
+            match event {
+                DomEvent::NodeStart { .. } => depth += 1,
+                DomEvent::NodeEnd => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn format_namespace(&self) -> Option<&'static str> {
+        Some("xml")
+    }
+}
+
+fn text_element(events: &mut Vec<DomEvent<'static>>, tag: &'static str, text: String) {
+    events.push(DomEvent::NodeStart {
+        tag: Cow::Borrowed(tag),
+        namespace: None,
+    });
+    events.push(DomEvent::ChildrenStart);
+    events.push(DomEvent::Text(Cow::Owned(text)));
+    events.push(DomEvent::ChildrenEnd);
+    events.push(DomEvent::NodeEnd);
+}
+
+fn feed(entries: usize) -> Vec<DomEvent<'static>> {
+    let mut events = vec![
+        DomEvent::NodeStart {
+            tag: Cow::Borrowed("feed"),
+            namespace: None,
+        },
+        DomEvent::ChildrenStart,
+    ];
+    text_element(&mut events, "title", "Feed".into());
+    for i in 0..entries {
+        events.push(DomEvent::NodeStart {
+            tag: Cow::Borrowed("entry"),
+            namespace: None,
+        });
+        events.push(DomEvent::Attribute {
+            name: Cow::Borrowed("id"),
+            value: Cow::Owned(format!("urn:entry:{i}")),
+            namespace: None,
+        });
+        events.push(DomEvent::ChildrenStart);
+        text_element(&mut events, "title", format!("Entry {i}"));
+        text_element(&mut events, "author-name", "Author".into());
+        text_element(&mut events, "updated-at", "2024-01-01T00:00:00Z".into());
+        text_element(&mut events, "category", "news".into());
+        events.push(DomEvent::ChildrenEnd);
+        events.push(DomEvent::NodeEnd);
+    }
+    events.push(DomEvent::ChildrenEnd);
+    events.push(DomEvent::NodeEnd);
+    events
+}
+
+fn deserialize_feed(bencher: Bencher, entries: usize) {
+    let events = feed(entries);
+    bencher
+        .with_inputs(|| Events(events.clone().into()))
+        .bench_values(|events| {
+            let mut de = DomDeserializer::new_owned(events);
+            let feed: Feed = de.deserialize().unwrap();
+            black_box(feed)
+        });
+}
+
+#[divan::bench(args = [10, 1_000, 10_000])]
+fn entries(bencher: Bencher, entries: usize) {
+    deserialize_feed(bencher, entries);
+}
+
+#[divan::bench(args = [10, 1_000, 10_000])]
+fn entries_uncached(bencher: Bencher, entries: usize) {
+    facet_dom::set_field_map_cache(false);
+    deserialize_feed(bencher, entries);
+    facet_dom::set_field_map_cache(true);
+}
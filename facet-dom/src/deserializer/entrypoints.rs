@@ -3,12 +3,15 @@
 //! This module contains the public API for creating deserializers and deserializing values.
 //! These are separated from the implementation details for easy auditing.
 
+use std::borrow::Cow;
+
 use facet_core::Facet;
 use facet_reflect::{HeapValue, Partial};
 
 use super::DomDeserializer;
-use crate::DomParser;
-use crate::error::DomDeserializeError;
+use crate::error::{DomDeserializeError, DomDeserializeErrorKind};
+use crate::path::PathTracker;
+use crate::{DomEvent, DomParser};
 
 impl<'de, P> DomDeserializer<'de, true, P>
 where
@@ -17,7 +20,9 @@ where
     /// Create a new DOM deserializer that can borrow strings from input.
     pub fn new(parser: P) -> Self {
         Self {
-            parser,
+            parser: PathTracker::new(parser),
+            errors: None,
+            value_span: None,
             _marker: std::marker::PhantomData,
         }
     }
@@ -30,7 +35,9 @@ where
     /// Create a new DOM deserializer that produces owned strings.
     pub fn new_owned(parser: P) -> Self {
         Self {
-            parser,
+            parser: PathTracker::new(parser),
+            errors: None,
+            value_span: None,
             _marker: std::marker::PhantomData,
         }
     }
@@ -46,10 +53,24 @@ where
         T: Facet<'de>,
     {
         let wip: Partial<'de, true> = Partial::alloc::<T>()?;
-        let partial = self.deserialize_into(wip)?;
-        let heap_value: HeapValue<'de, true> = partial.build()?;
+        self.skip_declaration().map_err(|e| self.locate(e))?;
+        let partial = self.deserialize_root(wip).map_err(|e| self.locate(e))?;
+        let heap_value: HeapValue<'de, true> = partial
+            .build()
+            .map_err(|e| self.locate(DomDeserializeError::from(e)))?;
         Ok(heap_value.materialize::<T>()?)
     }
+
+    /// Deserialize a value of type `T`, allowing borrowed strings from input, and
+    /// report every recoverable error instead of stopping at the first one.
+    ///
+    /// See [`DomDeserializer::deserialize_collecting`] for owned values.
+    pub fn deserialize_collecting<T>(&mut self) -> Result<T, Vec<DomDeserializeError<P::Error>>>
+    where
+        T: Facet<'de>,
+    {
+        self.collecting(|de| de.deserialize())
+    }
 }
 
 impl<'de, P> DomDeserializer<'de, false, P>
@@ -71,16 +92,141 @@ where
                 Partial::alloc_owned::<T>()?,
             )
         };
-        let partial = self.deserialize_into(wip)?;
+        self.skip_declaration().map_err(|e| self.locate(e))?;
+        let partial = self.deserialize_root(wip).map_err(|e| self.locate(e))?;
         // SAFETY: Same reasoning - with BORROW=false, HeapValue contains only
         // owned data. The 'de lifetime is phantom and we can safely transmute
         // back to 'static since T: Facet<'static>.
         #[allow(unsafe_code)]
         let heap_value: HeapValue<'static, false> = unsafe {
             core::mem::transmute::<HeapValue<'de, false>, HeapValue<'static, false>>(
-                partial.build()?,
+                partial
+                    .build()
+                    .map_err(|e| self.locate(DomDeserializeError::from(e)))?,
             )
         };
         Ok(heap_value.materialize::<T>()?)
     }
+
+    /// Deserialize a value of type `T` into an owned type, reporting every
+    /// recoverable error instead of stopping at the first one.
+    ///
+    /// Scalars that fail to parse, denied unknown elements and attributes, and
+    /// missing required fields are recorded with their path and span, and
+    /// deserialization carries on with the rest of the document. Any other error
+    /// ends it and is reported last. If anything was reported, the partially built
+    /// value is dropped and the errors are returned in document order.
+    pub fn deserialize_collecting<T>(&mut self) -> Result<T, Vec<DomDeserializeError<P::Error>>>
+    where
+        T: Facet<'static>,
+    {
+        self.collecting(|de| de.deserialize())
+    }
+
+    /// Deserialize the next element named `name` into an owned value of type `T`.
+    ///
+    /// Names and namespaces are compared like those of fields, following the
+    /// parser's [`name_matching`](DomParser::name_matching) and
+    /// [`namespace_matching`](DomParser::namespace_matching): `namespace` plays the
+    /// part of a field's `xml::ns`.
+    ///
+    /// Events before the match are consumed: elements with other names are descended
+    /// into, so matches are found at any depth, while text, attributes and comments
+    /// are discarded. The matching element is deserialized as if `T` were a field
+    /// named `name`, leaving the parser positioned right after it.
+    ///
+    /// Returns `Ok(None)` once the document is exhausted. Memory use is bounded by
+    /// a single matching element, which makes this suitable for iterating over the
+    /// entries of very large documents.
+    pub fn deserialize_next_named<T>(
+        &mut self,
+        name: &str,
+        namespace: Option<&str>,
+    ) -> Result<Option<T>, DomDeserializeError<P::Error>>
+    where
+        T: Facet<'static>,
+    {
+        let names = self.parser.name_matching();
+        let namespaces = self.parser.namespace_matching();
+        loop {
+            match self
+                .parser
+                .peek_event()
+                .map_err(DomDeserializeErrorKind::Parser)?
+            {
+                None => return Ok(None),
+                Some(DomEvent::NodeStart {
+                    tag,
+                    namespace: found,
+                }) if names.matches(tag, name)
+                    && namespaces.matches(found.as_deref(), namespace) =>
+                {
+                    break;
+                }
+                Some(_) => {
+                    self.parser
+                        .next_event()
+                        .map_err(DomDeserializeErrorKind::Parser)?;
+                }
+            }
+        }
+
+        // SAFETY: See `deserialize` above.
+        #[allow(unsafe_code)]
+        let wip: Partial<'de, false> = unsafe {
+            core::mem::transmute::<Partial<'static, false>, Partial<'de, false>>(
+                Partial::alloc_owned::<T>()?,
+            )
+        };
+        let partial = self
+            .deserialize_into_named(wip, Some(Cow::Owned(name.to_string())))
+            .map_err(|e| self.locate(e))?;
+        // SAFETY: See `deserialize` above.
+        #[allow(unsafe_code)]
+        let heap_value: HeapValue<'static, false> = unsafe {
+            core::mem::transmute::<HeapValue<'de, false>, HeapValue<'static, false>>(
+                partial
+                    .build()
+                    .map_err(|e| self.locate(DomDeserializeError::from(e)))?,
+            )
+        };
+        Ok(Some(heap_value.materialize::<T>()?))
+    }
+}
+
+impl<'de, const BORROW: bool, P> DomDeserializer<'de, BORROW, P>
+where
+    P: DomParser<'de>,
+{
+    /// Deserialize the root element, under the name the parser asks for if any.
+    fn deserialize_root(
+        &mut self,
+        wip: Partial<'de, BORROW>,
+    ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
+        match self.parser.root_name() {
+            Some(name) => {
+                let name = Cow::Owned(name.to_string());
+                self.deserialize_into_named(wip, Some(name))
+            }
+            None => self.deserialize_into(wip),
+        }
+    }
+
+    /// Run `deserialize` with recoverable errors recorded rather than returned.
+    fn collecting<T>(
+        &mut self,
+        deserialize: impl FnOnce(&mut Self) -> Result<T, DomDeserializeError<P::Error>>,
+    ) -> Result<T, Vec<DomDeserializeError<P::Error>>> {
+        self.errors = Some(Vec::new());
+        let result = deserialize(self);
+        let mut errors = self.errors.take().unwrap_or_default();
+        match result {
+            Ok(value) if errors.is_empty() => Ok(value),
+            Ok(_) => Err(errors),
+            Err(error) => {
+                errors.push(error);
+                Err(errors)
+            }
+        }
+    }
 }
@@ -2,10 +2,14 @@
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+#[cfg(feature = "bench")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
 
 use facet_core::{Def, Field, StructKind, StructType, Type, UserType};
 
 use crate::naming::{apply_rename_all, dom_key};
+use crate::{NameMatching, NamespaceMatching};
 use facet_singularize::singularize;
 
 /// Info about a field in a struct for deserialization purposes.
@@ -26,6 +30,207 @@ pub(crate) struct FieldInfo {
     pub namespace: Option<&'static str>,
 }
 
+/// How names in the input are matched against field names, set per parse.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct MatchOptions {
+    /// How element and attribute names are matched
+    pub names: NameMatching,
+    /// How namespaces are matched
+    pub namespaces: NamespaceMatching,
+}
+
+/// Look up a name, falling back to its folded form if the name matching folds names.
+fn lookup<'a, V>(
+    table: &'a HashMap<String, V>,
+    folded: &FoldedKeys,
+    name: &str,
+    matching: NameMatching,
+) -> Option<&'a V> {
+    table
+        .get(name)
+        .or_else(|| folded.get(matching, name).and_then(|key| table.get(key)))
+}
+
+/// The keys of a name table by folded form, for the name matchings that fold names.
+///
+/// Keys that fold alike lead to the same fields, since anything else is an
+/// [`Ambiguity`], so one key per folded form is enough.
+#[derive(Default)]
+struct FoldedKeys {
+    ignore_case: HashMap<String, String>,
+    ignore_naming: HashMap<String, String>,
+}
+
+impl FoldedKeys {
+    fn new<V>(table: &HashMap<String, V>) -> Self {
+        let mut folded = Self::default();
+        for key in table.keys() {
+            for (matching, keys) in [
+                (NameMatching::IgnoreCase, &mut folded.ignore_case),
+                (NameMatching::IgnoreNaming, &mut folded.ignore_naming),
+            ] {
+                keys.entry(matching.fold(key).into_owned())
+                    .or_insert_with(|| key.clone());
+            }
+        }
+        folded
+    }
+
+    fn get(&self, matching: NameMatching, name: &str) -> Option<&str> {
+        let keys = match matching {
+            NameMatching::Exact => return None,
+            NameMatching::IgnoreCase => &self.ignore_case,
+            NameMatching::IgnoreNaming => &self.ignore_naming,
+        };
+        keys.get(matching.fold(name).as_ref()).map(String::as_str)
+    }
+}
+
+/// Names that would match more than one field.
+#[derive(Clone, Debug)]
+pub(crate) struct Ambiguity {
+    /// The most exact name matching under which the names clash
+    pub matching: NameMatching,
+    /// The clashing names, as registered in the field map
+    pub names: Vec<String>,
+    /// The fields they lead to
+    pub fields: Vec<&'static str>,
+}
+
+/// A field that a name leads to: its position (parent and child index for
+/// flattened fields), name and namespace, and whether the name is one of its aliases.
+#[derive(Clone, Copy)]
+struct Target {
+    position: (usize, usize),
+    name: &'static str,
+    namespace: Option<&'static str>,
+    alias: bool,
+}
+
+/// Find the names of a table that lead to more than one field.
+///
+/// Under exact matching, names shared by several fields go to the first one,
+/// as they always have. The folding matchings are stricter: an alias may only
+/// name another field if their namespaces differ, and names that fold alike
+/// must lead to the same fields. Other shared names, like a field named after
+/// the singular of a list field, still go to the first one.
+fn find_ambiguities(
+    table: &HashMap<String, Vec<Target>>,
+    check_aliases: bool,
+    ambiguities: &mut Vec<Ambiguity>,
+) {
+    let mut keys: Vec<&String> = table.keys().collect();
+    keys.sort();
+
+    if check_aliases {
+        for key in &keys {
+            let targets = &table[*key];
+            let clash = targets.iter().enumerate().any(|(i, a)| {
+                targets[i + 1..].iter().any(|b| {
+                    (a.alias || b.alias) && a.position != b.position && a.namespace == b.namespace
+                })
+            });
+            if clash {
+                ambiguities.push(Ambiguity {
+                    matching: NameMatching::IgnoreCase,
+                    names: vec![(*key).clone()],
+                    fields: field_names(targets.iter()),
+                });
+            }
+        }
+    }
+
+    for matching in [NameMatching::IgnoreCase, NameMatching::IgnoreNaming] {
+        let mut groups: Vec<(Cow<'_, str>, Vec<&String>)> = Vec::new();
+        for key in &keys {
+            let folded = matching.fold(key);
+            match groups.iter_mut().find(|(f, _)| *f == folded) {
+                Some((_, group)) => group.push(key),
+                None => groups.push((folded, vec![key])),
+            }
+        }
+        for (_, group) in groups {
+            let positions = |key: &String| {
+                let mut positions: Vec<_> = table[key].iter().map(|t| t.position).collect();
+                positions.sort_unstable();
+                positions.dedup();
+                positions
+            };
+            let first = positions(group[0]);
+            if group[1..].iter().any(|key| positions(key) != first) {
+                ambiguities.push(Ambiguity {
+                    matching,
+                    names: group.iter().map(|key| (*key).clone()).collect(),
+                    fields: field_names(group.iter().flat_map(|key| &table[*key])),
+                });
+            }
+        }
+    }
+}
+
+/// Collect the targets of each name of a table.
+fn targets<V>(
+    table: &HashMap<String, V>,
+    targets: impl Fn(&str, &V) -> Vec<Target>,
+) -> HashMap<String, Vec<Target>> {
+    table
+        .iter()
+        .map(|(key, value)| (key.clone(), targets(key, value)))
+        .collect()
+}
+
+/// The distinct field names of some targets, in order of appearance.
+fn field_names<'a>(targets: impl Iterator<Item = &'a Target>) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    for target in targets {
+        if !names.contains(&target.name) {
+            names.push(target.name);
+        }
+    }
+    names
+}
+
+/// The aliases of a field: `#[facet(alias = "...")]` and any number of
+/// format-specific aliases like `#[facet(xml::alias = "...")]`.
+fn field_aliases(
+    field: &'static Field,
+    format_ns: Option<&'static str>,
+) -> impl Iterator<Item = &'static str> {
+    let format_aliases = field
+        .attributes
+        .iter()
+        .filter(move |attr| format_ns.is_some() && attr.ns == format_ns && attr.key == "alias")
+        .filter_map(|attr| attr.get_as::<&str>().copied());
+    field.alias.into_iter().chain(format_aliases)
+}
+
+/// Pick the field among those sharing a name that accepts `namespace`.
+///
+/// An exact namespace match wins over a field without a namespace constraint.
+fn pick_by_namespace<'a, T>(
+    candidates: &'a [T],
+    namespace: Option<&str>,
+    matching: NamespaceMatching,
+    field_namespace: impl Fn(&T) -> Option<&'static str>,
+) -> Option<&'a T> {
+    candidates
+        .iter()
+        .find(|c| field_namespace(c).is_some() && field_namespace(c) == namespace)
+        .or_else(|| {
+            candidates.iter().find(|c| {
+                field_namespace(c).is_none()
+                    && (matching != NamespaceMatching::Exact || namespace.is_none())
+            })
+        })
+        .or_else(|| {
+            if matching == NamespaceMatching::Ignore {
+                candidates.first()
+            } else {
+                None
+            }
+        })
+}
+
 /// Info about a flattened child field - a field inside a flattened struct that
 /// appears as a sibling in the XML.
 #[derive(Clone)]
@@ -86,6 +291,14 @@ pub(crate) struct StructFieldMap {
     pub tag_field: Option<FieldInfo>,
     /// The field marked with `xml::doctype` (captures DOCTYPE declaration)
     pub doctype_field: Option<FieldInfo>,
+    /// The field marked with `xml::comment` (collects comments)
+    pub comment_field: Option<FieldInfo>,
+    /// The field marked with `xml::pi` (collects processing instructions)
+    pub pi_field: Option<FieldInfo>,
+    /// The field marked with `xml::comment, xml::prolog` (collects comments before the root)
+    pub prolog_comment_field: Option<FieldInfo>,
+    /// The field marked with `xml::pi, xml::prolog` (collects processing instructions before the root)
+    pub prolog_pi_field: Option<FieldInfo>,
     /// The field marked with `#[facet(other)]` (fallback when root doesn't match)
     pub other_field: Option<FieldInfo>,
     /// For tuple structs: fields in order for positional matching.
@@ -112,6 +325,18 @@ pub(crate) struct StructFieldMap {
     pub has_flatten: bool,
     /// Catch-all elements field - matches any tag name (for item types with xml::tag field)
     pub catch_all_elements_field: Option<FieldInfo>,
+    /// Keys of `attribute_fields` by folded form
+    folded_attributes: FoldedKeys,
+    /// Keys of `element_fields` by folded form
+    folded_elements: FoldedKeys,
+    /// Keys of `elements_fields` by folded form
+    folded_elements_lists: FoldedKeys,
+    /// Keys of `flattened_attributes` by folded form
+    folded_flattened_attributes: FoldedKeys,
+    /// Keys of `flattened_children` by folded form
+    folded_flattened_children: FoldedKeys,
+    /// Names that lead to more than one field, found while building the map
+    ambiguities: Vec<Ambiguity>,
 }
 
 /// Compute the effective DOM key for a field, considering `rename_all` from the parent type.
@@ -120,7 +345,7 @@ pub(crate) struct StructFieldMap {
 /// 1. Explicit field rename (field.rename) - use as-is
 /// 2. Parent type's rename_all - apply transformation to field.name
 /// 3. Default lowerCamelCase conversion via dom_key
-fn field_dom_key<'a>(
+pub(crate) fn field_dom_key<'a>(
     field_name: &'a str,
     field_rename: Option<&'a str>,
     rename_all: Option<&str>,
@@ -137,7 +362,80 @@ fn field_dom_key<'a>(
     }
 }
 
+/// Identifies a field map: the struct definition's address, then `ns_all`,
+/// `rename_all` and the format namespace.
+///
+/// Struct definitions are keyed by address rather than `Shape::id` because enum
+/// variants have no shape of their own.
+type FieldMapKey = (
+    usize,
+    Option<&'static str>,
+    Option<&'static str>,
+    Option<&'static str>,
+);
+
+/// Field maps built so far, shared by all deserializations.
+///
+/// Entries are never evicted: the cache holds one map per struct definition and
+/// combination of `ns_all`, `rename_all` and format deserialized in the process.
+/// Since these come from static type information, its size is bounded by the
+/// types compiled into the program, not by the input.
+static FIELD_MAPS: LazyLock<RwLock<HashMap<FieldMapKey, Arc<StructFieldMap>>>> =
+    LazyLock::new(Default::default);
+
+/// Whether [`StructFieldMap::cached`] reuses maps from [`FIELD_MAPS`].
+#[cfg(feature = "bench")]
+static CACHE_FIELD_MAPS: AtomicBool = AtomicBool::new(true);
+
+/// Turn the process-wide field map cache on or off.
+///
+/// With the cache off every struct element builds its field map from scratch.
+/// This affects every deserialization in the process, so it is only available
+/// with the `bench` feature, for measuring what the cache saves.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub fn set_field_map_cache(enabled: bool) {
+    CACHE_FIELD_MAPS.store(enabled, Ordering::Relaxed);
+}
+
 impl StructFieldMap {
+    /// Get the field map for a struct definition, building it on first use.
+    ///
+    /// Maps only depend on static type information, so each is built once per
+    /// process instead of once per element. See [`StructFieldMap::new`] for the
+    /// parameters.
+    pub fn cached(
+        struct_def: &'static StructType,
+        ns_all: Option<&'static str>,
+        rename_all: Option<&'static str>,
+        format_ns: Option<&'static str>,
+    ) -> Arc<Self> {
+        #[cfg(feature = "bench")]
+        if !CACHE_FIELD_MAPS.load(Ordering::Relaxed) {
+            return Arc::new(Self::new(struct_def, ns_all, rename_all, format_ns));
+        }
+        let key = (
+            struct_def as *const StructType as usize,
+            ns_all,
+            rename_all,
+            format_ns,
+        );
+        if let Some(map) = FIELD_MAPS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return map.clone();
+        }
+        let map = Arc::new(Self::new(struct_def, ns_all, rename_all, format_ns));
+        FIELD_MAPS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key)
+            .or_insert(map)
+            .clone()
+    }
+
     /// Build the field map from a struct definition.
     ///
     /// The `ns_all` parameter is the default namespace for element fields that don't
@@ -163,6 +461,10 @@ impl StructFieldMap {
         let mut text_field = None;
         let mut tag_field = None;
         let mut doctype_field = None;
+        let mut comment_field = None;
+        let mut pi_field = None;
+        let mut prolog_comment_field = None;
+        let mut prolog_pi_field = None;
         let mut other_field = None;
         let mut flattened_children: HashMap<String, Vec<FlattenedChildInfo>> = HashMap::new();
         let mut flattened_attributes: HashMap<String, Vec<FlattenedChildInfo>> = HashMap::new();
@@ -172,6 +474,10 @@ impl StructFieldMap {
         let mut nested_flattened_attr_maps: Vec<NestedFlattenedMapInfo> = Vec::new();
         let mut has_flatten = false;
         let mut catch_all_elements_field: Option<FieldInfo> = None;
+        // Singularized names of list fields, registered after every other name so
+        // a field explicitly named like that is found first
+        let mut singular_children: Vec<(String, FlattenedChildInfo)> = Vec::new();
+        let mut singular_elements: Vec<(String, FieldInfo)> = Vec::new();
 
         for (idx, field) in struct_def.fields.iter().enumerate() {
             // Check if this field is flattened
@@ -271,12 +577,12 @@ impl StructFieldMap {
                                 .or_default()
                                 .push(flattened_child.clone());
 
-                            // Also register alias if present
-                            if let Some(alias) = child_field.alias {
+                            // Also register aliases if present
+                            for alias in field_aliases(child_field, format_ns) {
                                 flattened_attributes
                                     .entry(alias.to_string())
                                     .or_default()
-                                    .push(flattened_child);
+                                    .push(flattened_child.clone());
                             }
                         } else {
                             // Register as flattened element
@@ -290,19 +596,16 @@ impl StructFieldMap {
                             if (is_list || is_set) && !is_tuple && child_field.rename.is_none() {
                                 let singular_key = singularize(&child_key);
                                 if singular_key != *child_key {
-                                    flattened_children
-                                        .entry(singular_key)
-                                        .or_default()
-                                        .push(flattened_child.clone());
+                                    singular_children.push((singular_key, flattened_child.clone()));
                                 }
                             }
 
-                            // Also register alias if present
-                            if let Some(alias) = child_field.alias {
+                            // Also register aliases if present
+                            for alias in field_aliases(child_field, format_ns) {
                                 flattened_children
                                     .entry(alias.to_string())
                                     .or_default()
-                                    .push(flattened_child);
+                                    .push(flattened_child.clone());
                             }
                         }
                     }
@@ -365,12 +668,12 @@ impl StructFieldMap {
                         .or_default()
                         .push(info.clone());
 
-                    // Also register alias if present (aliases are used as-is, no conversion)
-                    if let Some(alias) = field.alias {
+                    // Also register aliases if present (aliases are used as-is, no conversion)
+                    for alias in field_aliases(field, format_ns) {
                         attribute_fields
                             .entry(alias.to_string())
                             .or_default()
-                            .push(info);
+                            .push(info.clone());
                     }
                 }
             } else if field.is_elements() {
@@ -454,6 +757,36 @@ impl StructFieldMap {
                     namespace,
                 };
                 doctype_field = Some(info);
+            } else if field.has_attr(Some("xml"), "comment") {
+                let info = FieldInfo {
+                    idx,
+                    field,
+                    is_list,
+                    is_array,
+                    is_set,
+                    is_tuple,
+                    namespace,
+                };
+                if field.has_attr(Some("xml"), "prolog") {
+                    prolog_comment_field = Some(info);
+                } else {
+                    comment_field = Some(info);
+                }
+            } else if field.has_attr(Some("xml"), "pi") {
+                let info = FieldInfo {
+                    idx,
+                    field,
+                    is_list,
+                    is_array,
+                    is_set,
+                    is_tuple,
+                    namespace,
+                };
+                if field.has_attr(Some("xml"), "prolog") {
+                    prolog_pi_field = Some(info);
+                } else {
+                    pi_field = Some(info);
+                }
             } else {
                 // Check if this field is marked as "other" - if so, register it as the fallback
                 // for tag mismatches, but ALSO register it as a normal element field so it
@@ -495,23 +828,27 @@ impl StructFieldMap {
                     let singular_key = singularize(&element_key);
                     // Only register if singularization actually changed the name
                     if singular_key != element_key {
-                        element_fields
-                            .entry(singular_key)
-                            .or_default()
-                            .push(info.clone());
+                        singular_elements.push((singular_key, info.clone()));
                     }
                 }
 
-                // Also register alias if present (aliases are used as-is, no conversion)
-                if let Some(alias) = field.alias {
+                // Also register aliases if present (aliases are used as-is, no conversion)
+                for alias in field_aliases(field, format_ns) {
                     element_fields
                         .entry(alias.to_string())
                         .or_default()
-                        .push(info);
+                        .push(info.clone());
                 }
             }
         }
 
+        for (key, child) in singular_children {
+            flattened_children.entry(key).or_default().push(child);
+        }
+        for (key, info) in singular_elements {
+            element_fields.entry(key).or_default().push(info);
+        }
+
         // For tuple structs, build positional field list
         let tuple_fields = if matches!(struct_def.kind, StructKind::TupleStruct | StructKind::Tuple)
         {
@@ -538,7 +875,51 @@ impl StructFieldMap {
             None
         };
 
+        let mut ambiguities = Vec::new();
+        let is_alias = |field, key: &str| field_aliases(field, format_ns).any(|a| a == key);
+        let direct = |key: &str, infos: &[FieldInfo]| -> Vec<Target> {
+            infos
+                .iter()
+                .map(|info| Target {
+                    position: (info.idx, 0),
+                    name: info.field.name,
+                    namespace: info.namespace,
+                    alias: is_alias(info.field, key),
+                })
+                .collect()
+        };
+        let flattened = |key: &str, infos: &Vec<FlattenedChildInfo>| -> Vec<Target> {
+            infos
+                .iter()
+                .map(|info| Target {
+                    position: (info.parent_idx, info.child_idx),
+                    name: info.child_info.field.name,
+                    namespace: info.child_info.namespace,
+                    alias: is_alias(info.child_info.field, key),
+                })
+                .collect()
+        };
+        // Names of elements lists overwrite each other, so only folding can clash
+        for (table, check_aliases) in [
+            (targets(&attribute_fields, |k, v| direct(k, v)), true),
+            (targets(&element_fields, |k, v| direct(k, v)), true),
+            (
+                targets(&elements_fields, |k, v| direct(k, std::slice::from_ref(v))),
+                false,
+            ),
+            (targets(&flattened_attributes, flattened), true),
+            (targets(&flattened_children, flattened), true),
+        ] {
+            find_ambiguities(&table, check_aliases, &mut ambiguities);
+        }
+
         Self {
+            folded_attributes: FoldedKeys::new(&attribute_fields),
+            folded_elements: FoldedKeys::new(&element_fields),
+            folded_elements_lists: FoldedKeys::new(&elements_fields),
+            folded_flattened_attributes: FoldedKeys::new(&flattened_attributes),
+            folded_flattened_children: FoldedKeys::new(&flattened_children),
+            ambiguities,
             attribute_fields,
             element_fields,
             elements_fields,
@@ -546,6 +927,10 @@ impl StructFieldMap {
             text_field,
             tag_field,
             doctype_field,
+            comment_field,
+            pi_field,
+            prolog_comment_field,
+            prolog_pi_field,
             other_field,
             tuple_fields,
             flattened_children,
@@ -561,43 +946,51 @@ impl StructFieldMap {
 
     /// Find an attribute field by name and namespace.
     ///
-    /// Returns `Some` if the name matches AND the namespace matches:
-    /// - If the field has no namespace constraint, it matches any namespace
-    /// - If the field has a namespace constraint, the incoming namespace must match exactly
+    /// Returns `Some` if the name matches AND the namespace matches according to
+    /// `matching.namespaces` (see [`NamespaceMatching`]).
     ///
     /// When multiple fields have the same name, prefers exact namespace match over wildcard.
-    pub fn find_attribute(&self, name: &str, namespace: Option<&str>) -> Option<&FieldInfo> {
-        self.attribute_fields.get(name).and_then(|fields| {
-            // First try to find an exact namespace match
-            let exact_match = fields
-                .iter()
-                .find(|info| info.namespace.is_some() && info.namespace == namespace);
-            if exact_match.is_some() {
-                return exact_match;
-            }
-            // Fall back to a field with no namespace constraint
-            fields.iter().find(|info| info.namespace.is_none())
+    pub fn find_attribute(
+        &self,
+        name: &str,
+        namespace: Option<&str>,
+        matching: MatchOptions,
+    ) -> Option<&FieldInfo> {
+        lookup(
+            &self.attribute_fields,
+            &self.folded_attributes,
+            name,
+            matching.names,
+        )
+        .and_then(|fields| {
+            pick_by_namespace(fields, namespace, matching.namespaces, |info| {
+                info.namespace
+            })
         })
     }
 
     /// Find an element field by tag name and namespace.
     ///
-    /// Returns `Some` if the name matches AND the namespace matches:
-    /// - If the field has no namespace constraint, it matches any namespace
-    /// - If the field has a namespace constraint, the incoming namespace must match exactly
+    /// Returns `Some` if the name matches AND the namespace matches according to
+    /// `matching.namespaces` (see [`NamespaceMatching`]).
     ///
     /// When multiple fields have the same name, prefers exact namespace match over wildcard.
-    pub fn find_element(&self, tag: &str, namespace: Option<&str>) -> Option<&FieldInfo> {
-        self.element_fields.get(tag).and_then(|fields| {
-            // First try to find an exact namespace match
-            let exact_match = fields
-                .iter()
-                .find(|info| info.namespace.is_some() && info.namespace == namespace);
-            if exact_match.is_some() {
-                return exact_match;
-            }
-            // Fall back to a field with no namespace constraint
-            fields.iter().find(|info| info.namespace.is_none())
+    pub fn find_element(
+        &self,
+        tag: &str,
+        namespace: Option<&str>,
+        matching: MatchOptions,
+    ) -> Option<&FieldInfo> {
+        lookup(
+            &self.element_fields,
+            &self.folded_elements,
+            tag,
+            matching.names,
+        )
+        .and_then(|fields| {
+            pick_by_namespace(fields, namespace, matching.namespaces, |info| {
+                info.namespace
+            })
         })
     }
 
@@ -608,19 +1001,18 @@ impl StructFieldMap {
         &self,
         tag: &str,
         namespace: Option<&str>,
+        matching: MatchOptions,
     ) -> Option<&FlattenedChildInfo> {
-        self.flattened_children.get(tag).and_then(|children| {
-            // First try to find an exact namespace match
-            let exact_match = children.iter().find(|info| {
-                info.child_info.namespace.is_some() && info.child_info.namespace == namespace
-            });
-            if exact_match.is_some() {
-                return exact_match;
-            }
-            // Fall back to a field with no namespace constraint
-            children
-                .iter()
-                .find(|info| info.child_info.namespace.is_none())
+        lookup(
+            &self.flattened_children,
+            &self.folded_flattened_children,
+            tag,
+            matching.names,
+        )
+        .and_then(|children| {
+            pick_by_namespace(children, namespace, matching.namespaces, |info| {
+                info.child_info.namespace
+            })
         })
     }
 
@@ -631,22 +1023,36 @@ impl StructFieldMap {
         &self,
         name: &str,
         namespace: Option<&str>,
+        matching: MatchOptions,
     ) -> Option<&FlattenedChildInfo> {
-        self.flattened_attributes.get(name).and_then(|children| {
-            // First try to find an exact namespace match
-            let exact_match = children.iter().find(|info| {
-                info.child_info.namespace.is_some() && info.child_info.namespace == namespace
-            });
-            if exact_match.is_some() {
-                return exact_match;
-            }
-            // Fall back to a field with no namespace constraint
-            children
-                .iter()
-                .find(|info| info.child_info.namespace.is_none())
+        lookup(
+            &self.flattened_attributes,
+            &self.folded_flattened_attributes,
+            name,
+            matching.names,
+        )
+        .and_then(|children| {
+            pick_by_namespace(children, namespace, matching.namespaces, |info| {
+                info.child_info.namespace
+            })
         })
     }
 
+    /// Find an `xml::elements` field by item element name.
+    pub fn find_elements_field(&self, tag: &str, matching: MatchOptions) -> Option<&FieldInfo> {
+        lookup(
+            &self.elements_fields,
+            &self.folded_elements_lists,
+            tag,
+            matching.names,
+        )
+    }
+
+    /// The first names that lead to more than one field under `matching`, if any.
+    pub fn ambiguity(&self, matching: NameMatching) -> Option<&Ambiguity> {
+        self.ambiguities.iter().find(|a| a.matching <= matching)
+    }
+
     /// Get a tuple field by position index.
     /// Returns None if this is not a tuple struct or if the index is out of bounds.
     pub fn get_tuple_field(&self, index: usize) -> Option<&FieldInfo> {
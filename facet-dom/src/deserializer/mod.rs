@@ -2,11 +2,12 @@
 
 use std::borrow::Cow;
 
-use facet_core::{Def, StructKind, Type, UserType};
-use facet_reflect::Partial;
+use facet_core::{Characteristic, Def, Shape, StructKind, Type, UserType, Variant};
+use facet_reflect::{Partial, Span};
 
-use crate::error::DomDeserializeError;
+use crate::error::{DomDeserializeError, DomDeserializeErrorKind};
 use crate::naming::to_element_name;
+use crate::path::PathTracker;
 use crate::trace;
 use crate::{AttributeRecord, DomEvent, DomParser, DomParserExt};
 
@@ -14,7 +15,11 @@ mod entrypoints;
 mod field_map;
 mod struct_deser;
 
-use struct_deser::StructDeserializer;
+use field_map::MatchOptions;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub use field_map::set_field_map_cache;
+use struct_deser::{DenyUnknown, StructDeserializer};
 
 /// Extension trait for chaining deserialization on `Partial`.
 pub(crate) trait PartialDeserializeExt<'de, const BORROW: bool, P: DomParser<'de>> {
@@ -56,8 +61,15 @@ impl<'de, const BORROW: bool, P: DomParser<'de>> PartialDeserializeExt<'de, BORR
 /// The `BORROW` parameter controls whether strings can be borrowed from the input:
 /// - `BORROW = true`: Allows zero-copy deserialization of `&str` and `Cow<str>`
 /// - `BORROW = false`: All strings are owned, input doesn't need to outlive result
-pub struct DomDeserializer<'de, const BORROW: bool, P> {
-    parser: P,
+pub struct DomDeserializer<'de, const BORROW: bool, P>
+where
+    P: DomParser<'de>,
+{
+    parser: PathTracker<'de, P>,
+    /// Recoverable errors seen so far, when collecting them instead of failing fast
+    errors: Option<Vec<DomDeserializeError<P::Error>>>,
+    /// Span of the text being set, when it differs from the current event's span
+    value_span: Option<Span>,
     _marker: std::marker::PhantomData<&'de ()>,
 }
 
@@ -105,11 +117,11 @@ where
         {
             let proxy_wip = wip
                 .begin_custom_deserialization_with_format(format_ns)
-                .map_err(DomDeserializeError::Reflect)?;
+                .map_err(DomDeserializeError::from)?;
             // Deserialize into proxy buffer with the same expected_name
             let proxy_wip = self.deserialize_into_inner(proxy_wip, expected_name)?;
             // Convert proxy -> target via TryFrom
-            return proxy_wip.end().map_err(DomDeserializeError::Reflect);
+            return proxy_wip.end().map_err(DomDeserializeError::from);
         }
 
         // Check for container-level proxy (e.g., #[facet(xml::proxy = ProxyType)] on the type)
@@ -118,13 +130,13 @@ where
         if wip.shape().effective_proxy(format_ns).is_some() {
             let (proxy_wip, found) = wip
                 .begin_custom_deserialization_from_shape_with_format(format_ns)
-                .map_err(DomDeserializeError::Reflect)?;
+                .map_err(DomDeserializeError::from)?;
 
             if found {
                 // Deserialize into proxy buffer with the same expected_name
                 let proxy_wip = self.deserialize_into_inner(proxy_wip, expected_name)?;
                 // Convert proxy -> target via TryFrom
-                return proxy_wip.end().map_err(DomDeserializeError::Reflect);
+                return proxy_wip.end().map_err(DomDeserializeError::from);
             }
             // Proxy check returned true but begin_custom_deserialization didn't find it
             // (shouldn't happen, but fall through to normal path)
@@ -134,6 +146,20 @@ where
         self.deserialize_into_inner(wip, expected_name)
     }
 
+    /// Skip the XML declaration, if any, before the root element.
+    pub(crate) fn skip_declaration(&mut self) -> Result<(), DomDeserializeError<P::Error>> {
+        while let Some(DomEvent::XmlDeclaration { .. }) = self
+            .parser
+            .peek_event()
+            .map_err(DomDeserializeErrorKind::Parser)?
+        {
+            self.parser
+                .next_event()
+                .map_err(DomDeserializeErrorKind::Parser)?;
+        }
+        Ok(())
+    }
+
     /// Inner deserialization logic, called after proxy handling.
     fn deserialize_into_inner(
         &mut self,
@@ -169,9 +195,9 @@ where
                     | Def::Pointer(_)
             )
         {
-            wip = wip.begin_inner().map_err(DomDeserializeError::Reflect)?;
+            wip = wip.begin_inner().map_err(DomDeserializeError::from)?;
             wip = self.deserialize_into_named(wip, expected_name)?;
-            wip = wip.end().map_err(DomDeserializeError::Reflect)?;
+            wip = wip.end().map_err(DomDeserializeError::from)?;
             return Ok(wip);
         }
 
@@ -189,10 +215,11 @@ where
                 Def::List(_) => self.deserialize_list(wip, expected_name),
                 Def::Set(_) => self.deserialize_set(wip, expected_name),
                 Def::Map(_) => self.deserialize_map(wip),
-                _ => Err(DomDeserializeError::Unsupported(format!(
+                _ => Err(DomDeserializeErrorKind::Unsupported(format!(
                     "unsupported type: {:?}",
                     shape.ty
-                ))),
+                ))
+                .into()),
             },
         }
     }
@@ -216,9 +243,9 @@ where
         let struct_def = match &shape.ty {
             Type::User(UserType::Struct(def)) => def,
             _ => {
-                return Err(DomDeserializeError::Unsupported(
-                    "expected struct type".into(),
-                ));
+                return Err(
+                    DomDeserializeErrorKind::Unsupported("expected struct type".into()).into(),
+                );
             }
         };
 
@@ -264,8 +291,7 @@ where
             .find(|attr| attr.ns == Some("xml") && attr.key == "ns_all")
             .and_then(|attr| attr.get_as::<&str>().copied());
 
-        // Check if deny_unknown_fields is set
-        let deny_unknown_fields = wip.shape().has_deny_unknown_fields_attr();
+        let deny_unknown = self.deny_unknown(wip.shape());
 
         StructDeserializer::new(
             self,
@@ -273,7 +299,7 @@ where
             ns_all,
             rename_all,
             expected_name,
-            deny_unknown_fields,
+            deny_unknown,
         )
         .deserialize(wip)
     }
@@ -313,9 +339,10 @@ where
                 let enum_def = match &enum_shape.ty {
                     Type::User(UserType::Enum(def)) => def,
                     _ => {
-                        return Err(DomDeserializeError::Unsupported(
+                        return Err(DomDeserializeErrorKind::Unsupported(
                             "expected enum type".into(),
-                        ));
+                        )
+                        .into());
                     }
                 };
 
@@ -337,19 +364,25 @@ where
                     // For tagged enums, match the element tag against variant names.
                     // Compute effective element name: use rename attribute if present,
                     // otherwise convert to lowerCamelCase.
-                    enum_def
-                        .variants
+                    let matching = self.parser.name_matching();
+                    let effective_name = |v: &Variant| -> Cow<'_, str> {
+                        if v.rename.is_some() {
+                            Cow::Borrowed(v.effective_name())
+                        } else {
+                            to_element_name(v.name)
+                        }
+                    };
+                    let variants = enum_def.variants;
+                    variants
                         .iter()
-                        .position(|v| {
-                            let effective_name: Cow<'_, str> = if v.rename.is_some() {
-                                Cow::Borrowed(v.effective_name())
-                            } else {
-                                to_element_name(v.name)
-                            };
-                            effective_name == tag
+                        .position(|v| effective_name(v) == tag)
+                        .or_else(|| {
+                            variants
+                                .iter()
+                                .position(|v| matching.matches(&tag, &effective_name(v)))
                         })
                         .or_else(|| enum_def.variants.iter().position(|v| v.is_custom_element()))
-                        .ok_or_else(|| DomDeserializeError::UnknownElement {
+                        .ok_or_else(|| DomDeserializeErrorKind::UnknownElement {
                             tag: tag.to_string(),
                         })?
                 };
@@ -409,15 +442,16 @@ where
                     }
                 }
             }
-            DomEvent::Text(_) => {
+            DomEvent::Text(_) | DomEvent::CData(_) => {
                 let text = self.parser.expect_text()?;
                 wip = self.deserialize_text_into_enum(wip, text)?;
             }
             other => {
-                return Err(DomDeserializeError::TypeMismatch {
+                return Err(DomDeserializeErrorKind::TypeMismatch {
                     expected: "NodeStart or Text",
                     got: format!("{other:?}"),
-                });
+                }
+                .into());
             }
         }
 
@@ -454,9 +488,10 @@ where
                 if self.parser.is_lenient() {
                     return Ok(wip);
                 } else {
-                    return Err(DomDeserializeError::Unsupported(
+                    return Err(DomDeserializeErrorKind::Unsupported(
                         "enum has no Text variant for text content".into(),
-                    ));
+                    )
+                    .into());
                 }
             }
         };
@@ -492,24 +527,25 @@ where
         // Must be at a NodeStart
         let event = self.parser.peek_event_or_eof("NodeStart for RawMarkup")?;
         if !matches!(event, DomEvent::NodeStart { .. }) {
-            return Err(DomDeserializeError::TypeMismatch {
+            return Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "NodeStart for RawMarkup",
                 got: format!("{event:?}"),
-            });
+            }
+            .into());
         }
 
         // Consume the NodeStart
         self.parser
             .next_event()
-            .map_err(DomDeserializeError::Parser)?;
+            .map_err(DomDeserializeErrorKind::Parser)?;
 
         // Try to capture raw - if not supported, fall back to error
         let raw = self
             .parser
             .capture_raw_node()
-            .map_err(DomDeserializeError::Parser)?
+            .map_err(DomDeserializeErrorKind::Parser)?
             .ok_or_else(|| {
-                DomDeserializeError::Unsupported("parser does not support raw capture".into())
+                DomDeserializeErrorKind::Unsupported("parser does not support raw capture".into())
             })?;
 
         // Set via the vtable's parse function
@@ -542,7 +578,7 @@ where
         let event = self.parser.peek_event_or_eof("Text or NodeStart")?;
         trace!(event = ?event, "peeked event in deserialize_scalar");
         match event {
-            DomEvent::Text(_) => {
+            DomEvent::Text(_) | DomEvent::CData(_) => {
                 trace!("deserialize_scalar: matched Text arm");
                 let text = self.parser.expect_text()?;
                 // Use set_string_value_with_proxy for format-specific proxy support
@@ -580,24 +616,41 @@ where
                         }
                         other => {
                             trace!(other = ?other, "deserialize_scalar: unexpected event in attr loop");
-                            return Err(DomDeserializeError::TypeMismatch {
+                            return Err(DomDeserializeErrorKind::TypeMismatch {
                                 expected: "Attribute or ChildrenStart or NodeEnd",
                                 got: format!("{other:?}"),
-                            });
+                            }
+                            .into());
                         }
                     }
                 }
 
                 trace!("deserialize_scalar: starting text content loop");
-                let mut text_content = String::new();
+                // Borrowed from the parser while there is a single text node
+                let mut text_content: Cow<'de, str> = Cow::Borrowed("");
+                // Span covering all text nodes, so parse errors point at the value itself
+                let mut text_span: Option<Span> = None;
                 loop {
                     let event = self.parser.peek_event_or_eof("Text or ChildrenEnd")?;
                     trace!(event = ?event, "deserialize_scalar: in text content loop");
                     match event {
-                        DomEvent::Text(_) => {
+                        DomEvent::Text(_) | DomEvent::CData(_) => {
                             let text = self.parser.expect_text()?;
                             trace!(text = %text, "deserialize_scalar: got text");
-                            text_content.push_str(&text);
+                            if text_content.is_empty() {
+                                text_content = text;
+                            } else {
+                                text_content.to_mut().push_str(&text);
+                            }
+                            if let Some(span) = self.parser.current_span() {
+                                text_span = Some(match text_span {
+                                    Some(start) => Span::new(
+                                        start.offset as usize,
+                                        span.end() - start.offset as usize,
+                                    ),
+                                    None => span,
+                                });
+                            }
                         }
                         DomEvent::ChildrenEnd => {
                             trace!("deserialize_scalar: got ChildrenEnd, breaking text loop");
@@ -607,16 +660,17 @@ where
                             trace!("deserialize_scalar: skipping nested NodeStart");
                             self.parser
                                 .skip_node()
-                                .map_err(DomDeserializeError::Parser)?;
+                                .map_err(DomDeserializeErrorKind::Parser)?;
                         }
                         DomEvent::Comment(_) => {
                             let _comment = self.parser.expect_comment()?;
                         }
                         other => {
-                            return Err(DomDeserializeError::TypeMismatch {
+                            return Err(DomDeserializeErrorKind::TypeMismatch {
                                 expected: "Text or ChildrenEnd",
                                 got: format!("{other:?}"),
-                            });
+                            }
+                            .into());
                         }
                     }
                 }
@@ -628,12 +682,16 @@ where
                 trace!(text_content = %text_content, "deserialize_scalar: setting string value");
 
                 // Use set_string_value_with_proxy for format-specific proxy support
-                self.set_string_value_with_proxy(wip, Cow::Owned(text_content))
+                self.value_span = text_span;
+                let result = self.set_string_value_with_proxy(wip, text_content);
+                self.value_span = None;
+                result.map_err(|e| self.locate_at(e, text_span))
             }
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "Text or NodeStart",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -726,10 +784,11 @@ where
                 let _ = self.parser.expect_node_start()?;
             }
             other => {
-                return Err(DomDeserializeError::TypeMismatch {
+                return Err(DomDeserializeErrorKind::TypeMismatch {
                     expected: "NodeStart for map wrapper",
                     got: format!("{other:?}"),
-                });
+                }
+                .into());
             }
         }
 
@@ -752,10 +811,11 @@ where
                     return Ok(wip.init_map()?);
                 }
                 other => {
-                    return Err(DomDeserializeError::TypeMismatch {
+                    return Err(DomDeserializeErrorKind::TypeMismatch {
                         expected: "Attribute or ChildrenStart or NodeEnd",
                         got: format!("{other:?}"),
-                    });
+                    }
+                    .into());
                 }
             }
         }
@@ -779,19 +839,20 @@ where
                     // Deserialize the value (element content)
                     wip = wip.begin_value()?.deserialize_with(self)?.end()?;
                 }
-                DomEvent::Text(_) | DomEvent::Comment(_) => {
+                DomEvent::Text(_) | DomEvent::CData(_) | DomEvent::Comment(_) => {
                     // Skip whitespace text and comments between map entries
-                    if matches!(event, DomEvent::Text(_)) {
+                    if matches!(event, DomEvent::Text(_) | DomEvent::CData(_)) {
                         self.parser.expect_text()?;
                     } else {
                         self.parser.expect_comment()?;
                     }
                 }
                 _ => {
-                    return Err(DomDeserializeError::TypeMismatch {
+                    return Err(DomDeserializeErrorKind::TypeMismatch {
                         expected: "map entry element",
                         got: format!("{event:?}"),
-                    });
+                    }
+                    .into());
                 }
             }
         }
@@ -868,6 +929,80 @@ where
         }
     }
 
+    /// Which unknown input is an error when deserializing a struct of `shape`.
+    ///
+    /// The parser's overrides win. Otherwise `deny_unknown_fields` covers both
+    /// attributes and elements, and the format's `deny_unknown_attributes` and
+    /// `deny_unknown_elements` attributes cover one each.
+    pub(crate) fn deny_unknown(&self, shape: &Shape) -> DenyUnknown {
+        let format_ns = self.parser.format_namespace();
+        let has_format_attr = |key: &str| {
+            format_ns.is_some()
+                && shape
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.ns == format_ns && attr.key == key)
+        };
+        let deny_fields = shape.has_deny_unknown_fields_attr();
+        DenyUnknown {
+            attributes: self
+                .parser
+                .deny_unknown_attributes()
+                .unwrap_or_else(|| deny_fields || has_format_attr("deny_unknown_attributes")),
+            elements: self
+                .parser
+                .deny_unknown_elements()
+                .unwrap_or_else(|| deny_fields || has_format_attr("deny_unknown_elements")),
+        }
+    }
+
+    /// How element names and namespaces are matched, as set by the parser.
+    pub(crate) fn match_options(&self) -> MatchOptions {
+        MatchOptions {
+            names: self.parser.name_matching(),
+            namespaces: self.parser.namespace_matching(),
+        }
+    }
+
+    /// Whether recoverable errors are recorded instead of returned.
+    pub(crate) fn is_collecting(&self) -> bool {
+        self.errors.is_some()
+    }
+
+    /// Record a recoverable error at the parser's current position.
+    ///
+    /// Only called when collecting errors; callers then carry on as if the
+    /// offending input had been absent.
+    pub(crate) fn record(&mut self, error: DomDeserializeError<P::Error>) {
+        let span = self.value_span.or_else(|| self.parser.current_span());
+        let error = self.locate_at(error, span);
+        trace!(%error, "recording recoverable error");
+        if let Some(errors) = &mut self.errors {
+            errors.push(error);
+        }
+    }
+
+    /// Annotate an error with the parser's current position in the source and tree.
+    pub(crate) fn locate(
+        &self,
+        error: DomDeserializeError<P::Error>,
+    ) -> DomDeserializeError<P::Error> {
+        self.locate_at(error, self.parser.current_span())
+    }
+
+    /// Annotate an error with the given source span, its line/column, and the
+    /// path to the element currently being deserialized.
+    pub(crate) fn locate_at(
+        &self,
+        error: DomDeserializeError<P::Error>,
+        span: Option<Span>,
+    ) -> DomDeserializeError<P::Error> {
+        let location = span.and_then(|span| self.parser.location_at(span.offset as usize));
+        error
+            .with_location(span, location)
+            .with_path(self.parser.path())
+    }
+
     /// Set a string value on the current partial, parsing it to the appropriate type.
     ///
     /// # Parser State Contract
@@ -880,44 +1015,87 @@ where
     /// (matching the serialization behavior). For other types, delegates to
     /// `facet_dessert::set_string_value` which handles parsing the string into the
     /// appropriate scalar type (String, &str, integers, floats, bools, etc.).
+    ///
+    /// When collecting errors, a value that does not parse is recorded and the
+    /// type's default is set instead, if it has one.
     pub(crate) fn set_string_value(
         &mut self,
-        mut wip: Partial<'de, BORROW>,
+        wip: Partial<'de, BORROW>,
         value: Cow<'de, str>,
     ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
-        // Handle enums specially - match variant names with lowerCamelCase conversion
-        // Skip Option (now reports as UserType::Enum) - facet_dessert handles it
-        if let Type::User(UserType::Enum(enum_def)) = &wip.shape().ty
-            && !matches!(wip.shape().def, Def::Option(_))
+        if self.is_collecting()
+            && let Err(error) = self.check_string_value(wip.shape(), &value)
         {
-            // Find matching variant
-            for (idx, variant) in enum_def.variants.iter().enumerate() {
-                // Only unit variants can be deserialized from a plain string
-                if variant.data.kind != StructKind::Unit {
-                    continue;
-                }
+            if !wip.shape().is(Characteristic::Default) {
+                return Err(error);
+            }
+            self.record(error);
+            return Ok(wip.set_default()?);
+        }
+        set_string_value_into(wip, value, self.parser.current_span())
+    }
 
-                // Compute the expected string for this variant (same logic as serialization)
-                let variant_str: Cow<'_, str> = if variant.rename.is_some() {
-                    Cow::Borrowed(variant.effective_name())
-                } else {
-                    to_element_name(variant.name)
-                };
+    /// Check that a string parses as a value of `shape`, without touching the
+    /// value being built.
+    ///
+    /// The string is set on a scratch value, so a failure leaves nothing to
+    /// clean up. Failures that only come from borrowing are not reported.
+    fn check_string_value(
+        &mut self,
+        shape: &'static Shape,
+        value: &str,
+    ) -> Result<(), DomDeserializeError<P::Error>> {
+        // SAFETY: the shape comes from a live `Partial`, so it describes a real type.
+        #[allow(unsafe_code)]
+        let scratch = unsafe { Partial::alloc_shape_owned(shape)? };
+        // SAFETY: the scratch value is owned and dropped before this returns, so the
+        // lifetime is phantom (see `deserialize`).
+        #[allow(unsafe_code)]
+        let scratch = unsafe {
+            core::mem::transmute::<Partial<'static, false>, Partial<'de, false>>(scratch)
+        };
+        match set_string_value_into(scratch, Cow::Owned(value.to_string()), None) {
+            Ok(_) => Ok(()),
+            Err(error) if matches!(error.kind(), DomDeserializeErrorKind::Unsupported(_)) => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
 
-                if value == variant_str {
-                    wip = wip.select_nth_variant(idx)?;
-                    return Ok(wip);
-                }
-            }
+    /// Set a processing instruction value.
+    ///
+    /// Structs receive the target and data in fields named `target` and `data`;
+    /// other types get the instruction as a single `"target data"` string.
+    pub(crate) fn set_processing_instruction(
+        &mut self,
+        mut wip: Partial<'de, BORROW>,
+        target: Cow<'de, str>,
+        data: Cow<'de, str>,
+    ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
+        if let Def::Option(_) = wip.shape().def {
+            wip = wip.begin_some()?;
+            return Ok(self.set_processing_instruction(wip, target, data)?.end()?);
+        }
 
-            // No match found - fall through to facet_dessert which will give a proper error
+        if let Type::User(UserType::Struct(struct_def)) = &wip.shape().ty {
+            for (idx, field) in struct_def.fields.iter().enumerate() {
+                let value = match field.name {
+                    "target" => target.clone(),
+                    "data" => data.clone(),
+                    _ => continue,
+                };
+                wip = self
+                    .set_string_value(wip.begin_nth_field(idx)?, value)?
+                    .end()?;
+            }
+            return Ok(wip);
         }
 
-        Ok(facet_dessert::set_string_value(
-            wip,
-            value,
-            self.parser.current_span(),
-        )?)
+        let text = if data.is_empty() {
+            target
+        } else {
+            Cow::Owned(format!("{target} {data}"))
+        };
+        self.set_string_value(wip, text)
     }
 
     /// Set a string value, handling field-level proxy conversion if present.
@@ -965,3 +1143,44 @@ where
         }
     }
 }
+
+/// Set a string value on `wip`, parsing it to the appropriate type.
+///
+/// For enums, matches the string against variant names using lowerCamelCase conversion
+/// (matching the serialization behavior). For other types, delegates to
+/// `facet_dessert::set_string_value`.
+fn set_string_value_into<'de, const BORROW: bool, E>(
+    mut wip: Partial<'de, BORROW>,
+    value: Cow<'de, str>,
+    span: Option<Span>,
+) -> Result<Partial<'de, BORROW>, DomDeserializeError<E>> {
+    // Handle enums specially - match variant names with lowerCamelCase conversion
+    // Skip Option (now reports as UserType::Enum) - facet_dessert handles it
+    if let Type::User(UserType::Enum(enum_def)) = &wip.shape().ty
+        && !matches!(wip.shape().def, Def::Option(_))
+    {
+        // Find matching variant
+        for (idx, variant) in enum_def.variants.iter().enumerate() {
+            // Only unit variants can be deserialized from a plain string
+            if variant.data.kind != StructKind::Unit {
+                continue;
+            }
+
+            // Compute the expected string for this variant (same logic as serialization)
+            let variant_str: Cow<'_, str> = if variant.rename.is_some() {
+                Cow::Borrowed(variant.effective_name())
+            } else {
+                to_element_name(variant.name)
+            };
+
+            if value == variant_str {
+                wip = wip.select_nth_variant(idx)?;
+                return Ok(wip);
+            }
+        }
+
+        // No match found - fall through to facet_dessert which will give a proper error
+    }
+
+    Ok(facet_dessert::set_string_value(wip, value, span)?)
+}
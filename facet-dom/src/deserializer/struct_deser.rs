@@ -2,18 +2,20 @@
 
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use facet_core::{Def, Shape, StructKind, StructType, Type, UserType};
+use facet_core::{Characteristic, Def, Field, Shape, StructKind, StructType, Type, UserType};
 use facet_reflect::Partial;
 
-use crate::error::DomDeserializeError;
+use crate::error::{DomDeserializeError, DomDeserializeErrorKind};
+use crate::path::PathTracker;
 use crate::trace;
 use crate::{AttributeRecord, DomEvent, DomParser, DomParserExt};
 
 use super::PartialDeserializeExt;
 use super::field_map::{
-    FieldInfo, FlattenedChildInfo, StructFieldMap, get_item_type_default_element_name,
-    get_item_type_rename,
+    FieldInfo, FlattenedChildInfo, MatchOptions, StructFieldMap, field_dom_key,
+    get_item_type_default_element_name, get_item_type_rename,
 };
 
 /// State for a flat sequence field being deserialized.
@@ -24,19 +26,28 @@ pub(crate) enum SeqState {
     Tuple { next_idx: usize },
 }
 
+/// Which unknown input is an error for a struct.
+#[derive(Clone, Copy)]
+pub(crate) struct DenyUnknown {
+    /// Attributes that no field accepts
+    pub attributes: bool,
+    /// Child elements that no field accepts
+    pub elements: bool,
+}
+
 /// Deserializer for struct types.
 ///
 /// Methods take `wip` as input and return it as output, threading it through.
 pub(crate) struct StructDeserializer<'de, 'p, const BORROW: bool, P: DomParser<'de>> {
     dom_deser: &'p mut super::DomDeserializer<'de, BORROW, P>,
-    field_map: StructFieldMap,
+    field_map: Arc<StructFieldMap>,
     struct_def: &'static StructType,
 
     /// Whether deferred mode is enabled (for flattened fields)
     using_deferred: bool,
 
-    /// Accumulated text content for xml::text field
-    text_content: String,
+    /// Accumulated text content for xml::text field, borrowed while it is a single node
+    text_content: Cow<'de, str>,
 
     /// Track which sequence fields have been started
     started_seqs: HashMap<usize, SeqState>,
@@ -47,6 +58,18 @@ pub(crate) struct StructDeserializer<'de, 'p, const BORROW: bool, P: DomParser<'
     /// Which elements lists have been started (keyed by field index)
     started_elements_lists: HashSet<usize>,
 
+    /// Comments collected for the xml::comment field
+    comments: Vec<Cow<'de, str>>,
+
+    /// Processing instructions (target, data) collected for the xml::pi field
+    processing_instructions: Vec<(Cow<'de, str>, Cow<'de, str>)>,
+
+    /// Comments before the root element, for the prolog xml::comment field
+    prolog_comments: Vec<Cow<'de, str>>,
+
+    /// Processing instructions before the root element, for the prolog xml::pi field
+    prolog_processing_instructions: Vec<(Cow<'de, str>, Cow<'de, str>)>,
+
     /// Whether we've started the xml::text list (for `Vec<String>` text fields)
     text_list_started: bool,
 
@@ -65,8 +88,14 @@ pub(crate) struct StructDeserializer<'de, 'p, const BORROW: bool, P: DomParser<'
     /// Whether the flattened enum list is currently active (we're inside it)
     flattened_enum_list_active: bool,
 
-    /// Whether unknown fields should cause an error
-    deny_unknown_fields: bool,
+    /// Whether unknown attributes and elements should cause an error
+    deny_unknown: DenyUnknown,
+
+    /// Naming transformation for fields without explicit renames
+    rename_all: Option<&'static str>,
+
+    /// How element names and namespaces are matched against fields
+    matching: MatchOptions,
 
     /// Position for tuple struct positional matching
     tuple_position: usize,
@@ -85,34 +114,54 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         ns_all: Option<&'static str>,
         rename_all: Option<&'static str>,
         expected_name: Cow<'static, str>,
-        deny_unknown_fields: bool,
+        deny_unknown: DenyUnknown,
     ) -> Self {
         let format_ns = dom_deser.parser.format_namespace();
-        let field_map = StructFieldMap::new(struct_def, ns_all, rename_all, format_ns);
+        let field_map = StructFieldMap::cached(struct_def, ns_all, rename_all, format_ns);
+        let matching = dom_deser.match_options();
         Self {
             dom_deser,
             field_map,
             struct_def,
             using_deferred: false,
-            text_content: String::new(),
+            text_content: Cow::Borrowed(""),
             started_seqs: HashMap::new(),
             active_seq_idx: None,
             started_elements_lists: HashSet::new(),
+            comments: Vec::new(),
+            processing_instructions: Vec::new(),
+            prolog_comments: Vec::new(),
+            prolog_processing_instructions: Vec::new(),
             text_list_started: false,
             attributes_list_started: false,
             started_flattened_maps: HashSet::new(),
             started_flattened_attr_maps: HashSet::new(),
             flattened_enum_list_started: false,
             flattened_enum_list_active: false,
-            deny_unknown_fields,
+            deny_unknown,
+            rename_all,
+            matching,
             tuple_position: 0,
             tag: Cow::Borrowed(""),
             expected_name,
         }
     }
 
+    /// Fail if names of several fields match the same input name under the
+    /// current name matching.
+    fn check_ambiguity(&self) -> Result<(), DomDeserializeError<P::Error>> {
+        match self.field_map.ambiguity(self.matching.names) {
+            Some(ambiguity) => Err(DomDeserializeErrorKind::AmbiguousName {
+                names: ambiguity.names.clone(),
+                fields: ambiguity.fields.clone(),
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
     /// Convenience accessor for the parser.
-    fn parser(&mut self) -> &mut P {
+    fn parser(&mut self) -> &mut PathTracker<'de, P> {
         &mut self.dom_deser.parser
     }
 
@@ -126,49 +175,44 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
             self.using_deferred = true;
         }
 
-        // Handle DOCTYPE if present and if struct has a doctype field
-        let has_doctype_field = self.field_map.doctype_field.is_some();
-        if has_doctype_field {
-            // Peek to see if there's a Doctype event
-            if let Ok(event) = self.parser().peek_event()
-                && let Some(DomEvent::Doctype(doctype_content)) = event
+        // Handle the prolog: DOCTYPE, comments and processing instructions before the root
+        loop {
+            match self
+                .parser()
+                .peek_event()
+                .map_err(DomDeserializeErrorKind::Parser)?
             {
-                // Clone the content before consuming the event
-                let doctype = doctype_content.to_string();
-                // Consume the Doctype event
-                let _ = self
-                    .parser()
-                    .next_event()
-                    .map_err(DomDeserializeError::Parser)?;
-                let idx = self.field_map.doctype_field.as_ref().unwrap().idx;
-                trace!(
-                    "→ .{} (doctype)",
-                    self.field_map.doctype_field.as_ref().unwrap().field.name
-                );
-                wip = self
-                    .dom_deser
-                    .set_string_value(wip.begin_nth_field(idx)?, Cow::Owned(doctype))?
-                    .end()?;
-            }
-        } else {
-            // No doctype field - skip any DOCTYPE events
-            while let Ok(event) = self.parser().peek_event() {
-                if let Some(DomEvent::Doctype(_)) = event {
-                    let _ = self
+                Some(DomEvent::Doctype(_)) => {
+                    let Some(DomEvent::Doctype(doctype)) = self
                         .parser()
                         .next_event()
-                        .map_err(DomDeserializeError::Parser)?;
-                } else {
-                    break;
+                        .map_err(DomDeserializeErrorKind::Parser)?
+                    else {
+                        unreachable!()
+                    };
+                    if let Some(info) = &self.field_map.doctype_field {
+                        let idx = info.idx;
+                        trace!("→ .{} (doctype)", info.field.name);
+                        wip = self
+                            .dom_deser
+                            .set_string_value(wip.begin_nth_field(idx)?, doctype)?
+                            .end()?;
+                    }
+                }
+                Some(DomEvent::Comment(_) | DomEvent::ProcessingInstruction { .. }) => {
+                    self.collect_markup(true)?;
                 }
+                _ => break,
             }
         }
 
         self.tag = self.parser().expect_node_start()?;
+        self.check_ambiguity()?;
 
         // Validate root element name matches expected, unless struct has a tag field
         // (which means it accepts any element name) or an other field (fallback for mismatches)
-        let tag_mismatch = self.field_map.tag_field.is_none() && self.tag != self.expected_name;
+        let tag_mismatch = self.field_map.tag_field.is_none()
+            && !self.matching.names.matches(&self.tag, &self.expected_name);
 
         if tag_mismatch {
             if let Some(info) = &self.field_map.other_field {
@@ -188,9 +232,10 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
 
                 return Ok(wip);
             } else {
-                return Err(DomDeserializeError::UnknownElement {
+                return Err(DomDeserializeErrorKind::UnknownElement {
                     tag: self.tag.to_string(),
-                });
+                }
+                .into());
             }
         }
 
@@ -210,6 +255,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         self.parser().expect_children_start()?;
         wip = self.process_children(wip)?;
         wip = self.cleanup(wip)?;
+        wip = self.check_required_fields(wip)?;
         self.parser().expect_children_end()?;
         self.parser().expect_node_end()?;
 
@@ -235,9 +281,9 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                         value,
                         namespace,
                     } = self.parser().expect_attribute()?;
-                    if let Some(info) = self
-                        .field_map
-                        .find_attribute(&name, namespace.as_ref().map(|c| c.as_ref()))
+                    if let Some(info) =
+                        self.field_map
+                            .find_attribute(&name, namespace.as_deref(), self.matching)
                     {
                         trace!("→ .{}", info.field.name);
                         // Use set_string_value_with_proxy to handle field-level proxies
@@ -247,7 +293,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                             .end()?;
                     } else if let Some(flattened) = self
                         .field_map
-                        .find_flattened_attribute(&name, namespace.as_ref().map(|c| c.as_ref()))
+                        .find_flattened_attribute(&name, namespace.as_deref(), self.matching)
                         .cloned()
                     {
                         // Handle attribute from a flattened struct (e.g., GlobalAttrs)
@@ -350,10 +396,16 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                             }
                         }
 
-                        if !handled && self.deny_unknown_fields {
-                            return Err(DomDeserializeError::UnknownAttribute {
-                                name: name.to_string(),
-                            });
+                        if !handled && self.deny_unknown.attributes {
+                            let error = DomDeserializeError::from(
+                                DomDeserializeErrorKind::UnknownAttribute {
+                                    name: name.to_string(),
+                                },
+                            );
+                            if !self.dom_deser.is_collecting() {
+                                return Err(error);
+                            }
+                            self.dom_deser.record(error);
                         }
                     }
                 }
@@ -365,10 +417,11 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                     return Ok(wip);
                 }
                 other => {
-                    return Err(DomDeserializeError::TypeMismatch {
+                    return Err(DomDeserializeErrorKind::TypeMismatch {
                         expected: "Attribute or ChildrenStart",
                         got: format!("{other:?}"),
-                    });
+                    }
+                    .into());
                 }
             }
         }
@@ -384,7 +437,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                 DomEvent::ChildrenEnd => {
                     break;
                 }
-                DomEvent::Text(_) => {
+                DomEvent::Text(_) | DomEvent::CData(_) => {
                     wip = self.handle_text(wip)?;
                 }
                 DomEvent::NodeStart { tag, namespace } => {
@@ -392,16 +445,96 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                     let namespace = namespace.clone();
                     wip = self.handle_child_element(wip, &tag, namespace.as_deref())?;
                 }
-                DomEvent::Comment(_) => {
-                    self.parser().expect_comment()?;
+                DomEvent::Comment(_) | DomEvent::ProcessingInstruction { .. } => {
+                    self.collect_markup(false)?;
                 }
                 other => {
-                    return Err(DomDeserializeError::TypeMismatch {
+                    return Err(DomDeserializeErrorKind::TypeMismatch {
                         expected: "child content",
                         got: format!("{other:?}"),
-                    });
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(wip)
+    }
+
+    /// Consume a comment or processing instruction, keeping it if the struct
+    /// has a field to collect it into.
+    ///
+    /// Items before the root element (`prolog`) only go to `xml::prolog` fields.
+    fn collect_markup(&mut self, prolog: bool) -> Result<(), DomDeserializeError<P::Error>> {
+        match self
+            .parser()
+            .next_event_or_eof("Comment or ProcessingInstruction")?
+        {
+            DomEvent::Comment(text) => {
+                let (field, comments) = if prolog {
+                    (
+                        &self.field_map.prolog_comment_field,
+                        &mut self.prolog_comments,
+                    )
+                } else {
+                    (&self.field_map.comment_field, &mut self.comments)
+                };
+                if field.is_some() {
+                    trace!(len = text.len(), prolog, "collecting comment");
+                    comments.push(text);
                 }
             }
+            DomEvent::ProcessingInstruction { target, data } => {
+                let (field, pis) = if prolog {
+                    (
+                        &self.field_map.prolog_pi_field,
+                        &mut self.prolog_processing_instructions,
+                    )
+                } else {
+                    (&self.field_map.pi_field, &mut self.processing_instructions)
+                };
+                if field.is_some() {
+                    trace!(%target, prolog, "collecting processing instruction");
+                    pis.push((target, data));
+                }
+            }
+            other => {
+                return Err(DomDeserializeErrorKind::TypeMismatch {
+                    expected: "Comment or ProcessingInstruction",
+                    got: format!("{other:?}"),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Write collected comments or processing instructions into their field.
+    ///
+    /// A list field receives every item; a single-valued field keeps the last one.
+    fn set_markup_field<T, F>(
+        &mut self,
+        mut wip: Partial<'de, BORROW>,
+        info: &FieldInfo,
+        items: Vec<T>,
+        set: F,
+    ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>>
+    where
+        F: Fn(
+            &mut super::DomDeserializer<'de, BORROW, P>,
+            Partial<'de, BORROW>,
+            T,
+        ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>>,
+    {
+        if info.is_list {
+            trace!(idx = info.idx, field_name = %info.field.name, len = items.len(), "setting markup list");
+            wip = wip.begin_nth_field(info.idx)?.init_list()?;
+            for item in items {
+                wip = set(self.dom_deser, wip.begin_list_item()?, item)?.end()?;
+            }
+            wip = wip.end()?;
+        } else if let Some(item) = items.into_iter().last() {
+            trace!(idx = info.idx, field_name = %info.field.name, "setting markup field");
+            wip = set(self.dom_deser, wip.begin_nth_field(info.idx)?, item)?.end()?;
         }
         Ok(wip)
     }
@@ -462,7 +595,11 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                 wip = self.dom_deser.set_string_value(wip, text)?.end()?;
             } else {
                 // Single String with xml::text - accumulate text
-                self.text_content.push_str(&text);
+                if self.text_content.is_empty() {
+                    self.text_content = text;
+                } else {
+                    self.text_content.to_mut().push_str(&text);
+                }
             }
         } else if !self.field_map.elements_fields.is_empty() {
             // html::elements / xml::elements collects child *elements*, not text nodes.
@@ -527,7 +664,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
     ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
         trace!(tag = %tag, namespace = ?namespace, "got child NodeStart");
 
-        if let Some(info) = self.field_map.find_element(tag, namespace) {
+        if let Some(info) = self.field_map.find_element(tag, namespace, self.matching) {
             // Check if the field has a field-level proxy - if so, the XML representation
             // is the proxy's shape, not the actual field type. A Vec<u32> with a string proxy
             // should be deserialized as a scalar (string), not as a flat sequence.
@@ -549,13 +686,20 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         } else if self.field_map.is_tuple() && tag == "item" {
             // Legacy support for <item> elements in tuple structs (deprecated)
             self.handle_tuple_item(wip)
-        } else if let Some(flattened) = self.field_map.find_flattened_child(tag, namespace).cloned()
+        } else if let Some(flattened) = self
+            .field_map
+            .find_flattened_child(tag, namespace, self.matching)
+            .cloned()
         {
             self.handle_flattened_child(wip, &flattened)
         } else if let Some(field_idx) = self.field_map.flattened_enum.as_ref().map(|e| e.field_idx)
         {
             self.handle_flattened_enum(wip, field_idx)
-        } else if let Some(info) = self.field_map.elements_fields.get(tag).cloned() {
+        } else if let Some(info) = self
+            .field_map
+            .find_elements_field(tag, self.matching)
+            .cloned()
+        {
             self.handle_elements_collection(wip, &info)
         } else if let Some(info) = self.field_map.catch_all_elements_field.clone() {
             // Catch-all elements field (item type has xml::tag, matches any element)
@@ -807,7 +951,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
             );
             self.parser()
                 .skip_node()
-                .map_err(DomDeserializeError::Parser)?;
+                .map_err(DomDeserializeErrorKind::Parser)?;
         }
         Ok(wip)
     }
@@ -963,10 +1107,11 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                     return Ok(String::new());
                 }
                 other => {
-                    return Err(DomDeserializeError::TypeMismatch {
+                    return Err(DomDeserializeErrorKind::TypeMismatch {
                         expected: "Attribute or ChildrenStart",
                         got: format!("{other:?}"),
-                    });
+                    }
+                    .into());
                 }
             }
         }
@@ -976,11 +1121,13 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         loop {
             match self.parser().peek_event_or_eof("text or ChildrenEnd")? {
                 DomEvent::ChildrenEnd => break,
-                DomEvent::Text(_) => text.push_str(&self.parser().expect_text()?),
+                DomEvent::Text(_) | DomEvent::CData(_) => {
+                    text.push_str(&self.parser().expect_text()?)
+                }
                 _ => self
                     .parser()
                     .skip_node()
-                    .map_err(DomDeserializeError::Parser)?,
+                    .map_err(DomDeserializeErrorKind::Parser)?,
             }
         }
         self.parser().expect_children_end()?;
@@ -993,15 +1140,19 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         wip: Partial<'de, BORROW>,
         tag: &str,
     ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
-        if wip.shape().has_deny_unknown_fields_attr() {
-            return Err(DomDeserializeError::UnknownElement {
+        if self.deny_unknown.elements {
+            let error = DomDeserializeError::from(DomDeserializeErrorKind::UnknownElement {
                 tag: tag.to_string(),
             });
+            if !self.dom_deser.is_collecting() {
+                return Err(error);
+            }
+            self.dom_deser.record(error);
         }
         trace!(tag, "skipping unknown element");
         self.parser()
             .skip_node()
-            .map_err(DomDeserializeError::Parser)?;
+            .map_err(DomDeserializeErrorKind::Parser)?;
         Ok(wip)
     }
 
@@ -1043,7 +1194,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                 .find(|attr| attr.ns == Some("xml") && attr.key == "ns_all")
                 .and_then(|attr| attr.get_as::<&str>().copied());
 
-            let deny_unknown_fields = inner_shape.has_deny_unknown_fields_attr();
+            let deny_unknown = self.dom_deser.deny_unknown(inner_shape);
 
             // If wrapped in Option, begin_some first
             if is_option {
@@ -1059,8 +1210,9 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                 ns_all,
                 None, // rename_all - none for regular structs
                 expected_name,
-                deny_unknown_fields,
+                deny_unknown,
             );
+            inner_deser.check_ambiguity()?;
 
             // The tag is already consumed, copy it to the inner deserializer
             inner_deser.tag = self.tag.clone();
@@ -1090,6 +1242,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
             inner_deser.parser().expect_children_start()?;
             wip = inner_deser.process_children(wip)?;
             wip = inner_deser.cleanup(wip)?;
+            wip = inner_deser.check_required_fields(wip)?;
             inner_deser.parser().expect_children_end()?;
             inner_deser.parser().expect_node_end()?;
 
@@ -1106,10 +1259,11 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
         } else {
             // For non-struct types, we can't really handle the already-consumed NodeStart.
             // This is an edge case - typically `other` is a struct type.
-            Err(DomDeserializeError::Unsupported(format!(
+            Err(DomDeserializeErrorKind::Unsupported(format!(
                 "other field must be a struct type, got {:?}",
                 field_shape.ty
-            )))
+            ))
+            .into())
         }
     }
 
@@ -1232,7 +1386,7 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
                 let text = std::mem::take(&mut self.text_content);
                 wip = self
                     .dom_deser
-                    .set_string_value(wip.begin_nth_field(idx)?, Cow::Owned(text))?
+                    .set_string_value(wip.begin_nth_field(idx)?, text)?
                     .end()?;
             }
         }
@@ -1256,6 +1410,82 @@ impl<'de, 'p, const BORROW: bool, P: DomParser<'de>> StructDeserializer<'de, 'p,
             }
         }
 
+        // Handle comment and processing instruction fields
+        if let Some(info) = self.field_map.comment_field.clone() {
+            let comments = std::mem::take(&mut self.comments);
+            wip = self.set_markup_field(wip, &info, comments, |de, wip, text| {
+                de.set_string_value(wip, text)
+            })?;
+        }
+        if let Some(info) = self.field_map.pi_field.clone() {
+            let pis = std::mem::take(&mut self.processing_instructions);
+            wip = self.set_markup_field(wip, &info, pis, |de, wip, (target, data)| {
+                de.set_processing_instruction(wip, target, data)
+            })?;
+        }
+        if let Some(info) = self.field_map.prolog_comment_field.clone() {
+            let comments = std::mem::take(&mut self.prolog_comments);
+            wip = self.set_markup_field(wip, &info, comments, |de, wip, text| {
+                de.set_string_value(wip, text)
+            })?;
+        }
+        if let Some(info) = self.field_map.prolog_pi_field.clone() {
+            let pis = std::mem::take(&mut self.prolog_processing_instructions);
+            wip = self.set_markup_field(wip, &info, pis, |de, wip, (target, data)| {
+                de.set_processing_instruction(wip, target, data)
+            })?;
+        }
+
         Ok(wip)
     }
+
+    /// When collecting errors, record every required field that was never set,
+    /// then fill it with its type's default so the struct can still be finished.
+    ///
+    /// A missing field whose type has no default ends deserialization. Outside
+    /// collecting mode this does nothing, and `end()` reports the first missing field.
+    fn check_required_fields(
+        &mut self,
+        mut wip: Partial<'de, BORROW>,
+    ) -> Result<Partial<'de, BORROW>, DomDeserializeError<P::Error>> {
+        if !self.dom_deser.is_collecting() {
+            return Ok(wip);
+        }
+        let container_has_default = wip.shape().has_default_attr();
+        for (idx, field) in self.struct_def.fields.iter().enumerate() {
+            if field.is_flattened()
+                || !is_required(field, container_has_default)
+                || wip.is_field_set(idx)?
+            {
+                continue;
+            }
+            let name = field_dom_key(field.name, field.rename, self.rename_all).into_owned();
+            let error = DomDeserializeError::from(if field.is_attribute() {
+                DomDeserializeErrorKind::MissingAttribute { name }
+            } else {
+                DomDeserializeErrorKind::MissingElement { tag: name }
+            });
+            if !field.shape().is(Characteristic::Default) {
+                return Err(error);
+            }
+            self.dom_deser.record(error);
+            wip = wip.set_nth_field_to_default(idx)?;
+        }
+        Ok(wip)
+    }
+}
+
+/// Whether a field must be present in the input, mirroring the rules `Partial`
+/// uses to fill in fields that were never set.
+fn is_required(field: &Field, container_has_default: bool) -> bool {
+    let shape = field.shape();
+    let has_default = shape.is(Characteristic::Default);
+    let is_empty_struct =
+        matches!(shape.ty, Type::User(UserType::Struct(def)) if def.fields.is_empty());
+    !(field.has_default()
+        || (has_default
+            && (matches!(shape.def, Def::Option(_))
+                || field.should_skip_deserializing()
+                || is_empty_struct
+                || container_has_default)))
 }
@@ -2,9 +2,32 @@
 
 use std::fmt;
 
-/// Error type for DOM deserialization.
+use crate::ElementPath;
+
+/// Error type for DOM deserialization: what went wrong, and where.
+///
+/// Match on [`DomDeserializeError::kind`] to tell errors apart.
 #[derive(Debug)]
-pub enum DomDeserializeError<E> {
+pub struct DomDeserializeError<E> {
+    kind: DomDeserializeErrorKind<E>,
+    /// Boxed so that errors stay small on the `Result` path.
+    context: Box<ErrorContext>,
+}
+
+/// Where a [`DomDeserializeError`] happened.
+#[derive(Debug, Default)]
+struct ErrorContext {
+    /// Byte span of the event being processed when the error occurred.
+    span: Option<facet_reflect::Span>,
+    /// Line and column of the start of `span`, if the parser can compute it.
+    location: Option<SourceLocation>,
+    /// Path to the element or attribute being deserialized.
+    path: Option<ElementPath>,
+}
+
+/// What went wrong during DOM deserialization.
+#[derive(Debug)]
+pub enum DomDeserializeErrorKind<E> {
     /// Parser error.
     Parser(E),
 
@@ -46,31 +69,147 @@ pub enum DomDeserializeError<E> {
     /// Missing required attribute.
     MissingAttribute {
         /// The attribute name.
-        name: &'static str,
+        name: String,
+    },
+
+    /// Missing required child element.
+    MissingElement {
+        /// The element tag name.
+        tag: String,
+    },
+
+    /// Names of several fields of a struct match the same input name.
+    ///
+    /// Found when building the struct's field lookup, before any of its input is read.
+    AmbiguousName {
+        /// The clashing names, as declared on the fields.
+        names: Vec<String>,
+        /// The fields they belong to.
+        fields: Vec<&'static str>,
     },
 
     /// Unsupported type.
     Unsupported(String),
 }
 
+impl<E> DomDeserializeError<E> {
+    /// What went wrong.
+    pub fn kind(&self) -> &DomDeserializeErrorKind<E> {
+        &self.kind
+    }
+
+    /// What went wrong, dropping where.
+    pub fn into_kind(self) -> DomDeserializeErrorKind<E> {
+        self.kind
+    }
+
+    /// Attach a source span and line/column to this error.
+    ///
+    /// Information the error already carries is kept, so the innermost (most
+    /// precise) location wins.
+    pub fn with_location(
+        mut self,
+        span: Option<facet_reflect::Span>,
+        location: Option<SourceLocation>,
+    ) -> Self {
+        // Span and location describe the same point, so only fill them together
+        let context = &mut self.context;
+        if context.span.is_none() && context.location.is_none() {
+            context.span = span;
+            context.location = location;
+        }
+        self
+    }
+
+    /// Attach the path of the element or attribute being deserialized.
+    ///
+    /// An error that already carries a path keeps it.
+    pub fn with_path(mut self, path: ElementPath) -> Self {
+        self.context.path.get_or_insert(path);
+        self
+    }
+
+    /// Byte span in the source document where the error occurred, if known.
+    pub fn span(&self) -> Option<facet_reflect::Span> {
+        self.context.span
+    }
+
+    /// Line and column in the source document where the error occurred, if known.
+    pub fn location(&self) -> Option<SourceLocation> {
+        self.context.location
+    }
+
+    /// Path to the element or attribute where the error occurred, if known.
+    pub fn path(&self) -> Option<&ElementPath> {
+        self.context.path.as_ref()
+    }
+}
+
+impl<E> From<DomDeserializeErrorKind<E>> for DomDeserializeError<E> {
+    fn from(kind: DomDeserializeErrorKind<E>) -> Self {
+        Self {
+            kind,
+            context: Box::default(),
+        }
+    }
+}
+
+/// A 1-based line and column position in a source document.
+///
+/// Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number, starting at 1.
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// Compute the line and column of a byte offset in `input`.
+    ///
+    /// Offsets past the end of the input are clamped to the end.
+    pub fn from_offset(input: &[u8], offset: usize) -> Self {
+        let prefix = &input[..offset.min(input.len())];
+        let line_start = prefix
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |pos| pos + 1);
+        let line = prefix.iter().filter(|&&b| b == b'\n').count() + 1;
+        // Count UTF-8 lead bytes so multi-byte characters occupy one column
+        let column = prefix[line_start..]
+            .iter()
+            .filter(|&&b| (b & 0xC0) != 0x80)
+            .count()
+            + 1;
+        Self { line, column }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 impl<E> From<facet_reflect::ReflectError> for DomDeserializeError<E> {
     fn from(e: facet_reflect::ReflectError) -> Self {
         crate::trace!("🚨 ReflectError -> DomDeserializeError: {e}");
-        Self::Reflect(e)
+        DomDeserializeErrorKind::Reflect(e).into()
     }
 }
 
 impl<E> From<facet_reflect::AllocError> for DomDeserializeError<E> {
     fn from(e: facet_reflect::AllocError) -> Self {
         crate::trace!("🚨 AllocError -> DomDeserializeError: {e}");
-        Self::Alloc(e)
+        DomDeserializeErrorKind::Alloc(e).into()
     }
 }
 
 impl<E> From<facet_reflect::ShapeMismatchError> for DomDeserializeError<E> {
     fn from(e: facet_reflect::ShapeMismatchError) -> Self {
         crate::trace!("🚨 ShapeMismatchError -> DomDeserializeError: {e}");
-        Self::ShapeMismatch(e)
+        DomDeserializeErrorKind::ShapeMismatch(e).into()
     }
 }
 
@@ -78,15 +217,17 @@ impl<E> From<facet_dessert::DessertError> for DomDeserializeError<E> {
     fn from(e: facet_dessert::DessertError) -> Self {
         crate::trace!("🚨 DessertError -> DomDeserializeError: {e}");
         match e {
-            facet_dessert::DessertError::Reflect { error, .. } => Self::Reflect(error),
+            facet_dessert::DessertError::Reflect { error, .. } => {
+                DomDeserializeErrorKind::Reflect(error).into()
+            }
             facet_dessert::DessertError::CannotBorrow { message } => {
-                Self::Unsupported(message.into_owned())
+                DomDeserializeErrorKind::Unsupported(message.into_owned()).into()
             }
         }
     }
 }
 
-impl<E: std::error::Error> fmt::Display for DomDeserializeError<E> {
+impl<E: std::error::Error> fmt::Display for DomDeserializeErrorKind<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Parser(e) => write!(f, "parser error: {e}"),
@@ -100,18 +241,39 @@ impl<E: std::error::Error> fmt::Display for DomDeserializeError<E> {
             Self::UnknownElement { tag } => write!(f, "unknown element: <{tag}>"),
             Self::UnknownAttribute { name } => write!(f, "unknown attribute: {name}"),
             Self::MissingAttribute { name } => write!(f, "missing required attribute: {name}"),
+            Self::MissingElement { tag } => write!(f, "missing required element: <{tag}>"),
+            Self::AmbiguousName { names, fields } => write!(
+                f,
+                "ambiguous name {}: matches fields {}",
+                names.join(" / "),
+                fields.join(", ")
+            ),
             Self::Unsupported(msg) => write!(f, "unsupported: {msg}"),
         }
     }
 }
 
+impl<E: std::error::Error> fmt::Display for DomDeserializeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.context.location, self.context.span) {
+            (Some(location), _) => write!(f, "{location}: ")?,
+            (None, Some(span)) => write!(f, "at byte {}: ", span.offset)?,
+            (None, None) => {}
+        }
+        if let Some(path) = &self.context.path {
+            write!(f, "{path}: ")?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
 impl<E: std::error::Error + 'static> std::error::Error for DomDeserializeError<E> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::Parser(e) => Some(e),
-            Self::Reflect(e) => Some(e),
-            Self::Alloc(e) => Some(e),
-            Self::ShapeMismatch(e) => Some(e),
+        match &self.kind {
+            DomDeserializeErrorKind::Parser(e) => Some(e),
+            DomDeserializeErrorKind::Reflect(e) => Some(e),
+            DomDeserializeErrorKind::Alloc(e) => Some(e),
+            DomDeserializeErrorKind::ShapeMismatch(e) => Some(e),
             _ => None,
         }
     }
@@ -64,6 +64,12 @@ pub enum DomEvent<'a> {
     /// Only valid between `ChildrenStart` and `ChildrenEnd`.
     Text(Cow<'a, str>),
 
+    /// Content of a CDATA section (XML), without the `<![CDATA[`/`]]>` markers.
+    ///
+    /// Deserializers treat it like `Text`; it is kept separate so documents can be
+    /// rewritten without losing the section.
+    CData(Cow<'a, str>),
+
     /// A comment (usually ignored during deserialization).
     Comment(Cow<'a, str>),
 
@@ -77,6 +83,18 @@ pub enum DomEvent<'a> {
 
     /// DOCTYPE declaration (HTML5).
     Doctype(Cow<'a, str>),
+
+    /// The XML declaration, `<?xml version="1.0" encoding="..." standalone="..."?>`.
+    ///
+    /// Only valid at the very start of a document.
+    XmlDeclaration {
+        /// The `version` pseudo-attribute.
+        version: Cow<'a, str>,
+        /// The `encoding` pseudo-attribute, as written.
+        encoding: Option<Cow<'a, str>>,
+        /// The `standalone` pseudo-attribute (`yes` is `true`).
+        standalone: Option<bool>,
+    },
 }
 
 impl<'a> DomEvent<'a> {
@@ -100,6 +118,11 @@ impl<'a> DomEvent<'a> {
         matches!(self, DomEvent::Text(_))
     }
 
+    /// Returns true if this is a `CData` event.
+    pub fn is_cdata(&self) -> bool {
+        matches!(self, DomEvent::CData(_))
+    }
+
     /// Returns true if this is `ChildrenStart`.
     pub fn is_children_start(&self) -> bool {
         matches!(self, DomEvent::ChildrenStart)
@@ -195,7 +218,32 @@ impl std::fmt::Display for TraceFmt<'_, '_> {
             DomEvent::ProcessingInstruction { target, data } => {
                 write!(f, "ProcessingInstruction <?{target} {data}?>")
             }
+            DomEvent::CData(t) => {
+                let preview: String = t.chars().take(40).collect();
+                write!(
+                    f,
+                    "CData {}{}{}",
+                    "<![CDATA[".green(),
+                    preview,
+                    "]]>".green()
+                )
+            }
             DomEvent::Doctype(d) => write!(f, "Doctype <!DOCTYPE {d}>"),
+            DomEvent::XmlDeclaration {
+                version,
+                encoding,
+                standalone,
+            } => {
+                write!(f, "XmlDeclaration <?xml version=\"{version}\"")?;
+                if let Some(encoding) = encoding {
+                    write!(f, " encoding=\"{encoding}\"")?;
+                }
+                if let Some(standalone) = standalone {
+                    let standalone = if *standalone { "yes" } else { "no" };
+                    write!(f, " standalone=\"{standalone}\"")?;
+                }
+                write!(f, "?>")
+            }
         }
     }
 }
@@ -14,6 +14,7 @@ mod event;
 pub mod naming;
 mod parser;
 mod parser_ext;
+mod path;
 mod raw_markup;
 mod serializer;
 mod tracing_macros;
@@ -23,5 +24,6 @@ pub use error::*;
 pub use event::*;
 pub use parser::*;
 pub use parser_ext::*;
+pub use path::{ElementPath, PathSegment};
 pub use raw_markup::*;
 pub use serializer::*;
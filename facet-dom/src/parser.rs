@@ -28,6 +28,13 @@ pub trait DomParser<'de> {
         None
     }
 
+    /// Translate a byte offset in the source document into a line and column.
+    ///
+    /// Returns `None` if the parser does not retain the source text.
+    fn location_at(&self, _offset: usize) -> Option<crate::SourceLocation> {
+        None
+    }
+
     /// Whether this parser is lenient about text in unexpected places.
     ///
     /// HTML parsers return `true` - text without a corresponding field is silently discarded.
@@ -36,6 +43,38 @@ pub trait DomParser<'de> {
         false
     }
 
+    /// Whether unknown attributes are errors, overriding the target types.
+    ///
+    /// `Some(true)` rejects and `Some(false)` ignores attributes that no field
+    /// accepts, whatever the types say. `None` (the default) leaves it to each type's
+    /// `deny_unknown_fields` or format-specific `deny_unknown_attributes` attribute.
+    fn deny_unknown_attributes(&self) -> Option<bool> {
+        None
+    }
+
+    /// Whether unknown child elements are errors, overriding the target types.
+    ///
+    /// Like [`DomParser::deny_unknown_attributes`], for elements and the
+    /// format-specific `deny_unknown_elements` attribute.
+    fn deny_unknown_elements(&self) -> Option<bool> {
+        None
+    }
+
+    /// How element and attribute names are matched against fields, types and variants.
+    fn name_matching(&self) -> NameMatching {
+        NameMatching::default()
+    }
+
+    /// How the namespaces of elements and attributes are matched against fields.
+    fn namespace_matching(&self) -> NamespaceMatching {
+        NamespaceMatching::default()
+    }
+
+    /// The name the root element must have, overriding the one derived from the target type.
+    fn root_name(&self) -> Option<&str> {
+        None
+    }
+
     /// Returns the format namespace for this parser (e.g., "xml", "html").
     ///
     /// This is used to select format-specific proxy types when a field has
@@ -56,3 +95,74 @@ pub trait DomParser<'de> {
         Ok(None)
     }
 }
+
+/// How element and attribute names are matched against fields, types and variants.
+///
+/// An exact match is always preferred. Under the folding matchings, names that
+/// only match several fields because of the folding, and aliases that name
+/// another field, are reported as [`DomDeserializeErrorKind::AmbiguousName`]
+/// before any input is read. Under [`NameMatching::Exact`] such a name goes to
+/// the first field declared.
+///
+/// [`DomDeserializeErrorKind::AmbiguousName`]: crate::DomDeserializeErrorKind::AmbiguousName
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameMatching {
+    /// Names must match exactly.
+    #[default]
+    Exact,
+    /// Names match regardless of ASCII case, so `<Title>` fills a field named `title`.
+    IgnoreCase,
+    /// Names match regardless of ASCII case and of `_`, `-` and `.` separators, so
+    /// `<UserName>`, `<username>` and `<user_name>` all fill a field named `user_name`.
+    IgnoreNaming,
+}
+
+impl NameMatching {
+    /// Whether `name` from the input matches `expected`.
+    pub fn matches(self, name: &str, expected: &str) -> bool {
+        name == expected || (self != Self::Exact && self.fold(name) == self.fold(expected))
+    }
+
+    /// The form of `name` that is compared under this matching.
+    pub(crate) fn fold(self, name: &str) -> std::borrow::Cow<'_, str> {
+        match self {
+            Self::Exact => std::borrow::Cow::Borrowed(name),
+            Self::IgnoreCase => std::borrow::Cow::Owned(name.to_ascii_lowercase()),
+            Self::IgnoreNaming => std::borrow::Cow::Owned(
+                name.chars()
+                    .filter(|c| !matches!(c, '_' | '-' | '.'))
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// How the namespaces of elements and attributes are matched against fields.
+///
+/// A field is namespaced when it has an `xml::ns` attribute or its container has
+/// `xml::ns_all`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NamespaceMatching {
+    /// Namespaced fields only match that namespace, other fields match any namespace.
+    #[default]
+    IfDeclared,
+    /// Namespaced fields only match that namespace, other fields only match names
+    /// without a namespace.
+    Exact,
+    /// Fields match by local name whatever the namespace, preferring a namespace match
+    /// when several fields share the name.
+    Ignore,
+}
+
+impl NamespaceMatching {
+    /// Whether `namespace` from the input matches a field that expects `expected`.
+    pub fn matches(self, namespace: Option<&str>, expected: Option<&str>) -> bool {
+        match (self, expected) {
+            (Self::Ignore, _) => true,
+            (_, Some(expected)) => namespace == Some(expected),
+            (Self::Exact, None) => namespace.is_none(),
+            (Self::IfDeclared, None) => true,
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use std::borrow::Cow;
 
 use crate::trace;
-use crate::{DomDeserializeError, DomEvent, DomParser};
+use crate::{DomDeserializeError, DomDeserializeErrorKind, DomEvent, DomParser};
 
 /// Extension trait adding convenience methods to any `DomParser`.
 pub trait DomParserExt<'de>: DomParser<'de> {
@@ -14,8 +14,8 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     ) -> Result<DomEvent<'de>, DomDeserializeError<Self::Error>> {
         let event = self
             .next_event()
-            .map_err(DomDeserializeError::Parser)?
-            .ok_or(DomDeserializeError::UnexpectedEof { expected })?;
+            .map_err(DomDeserializeErrorKind::Parser)?
+            .ok_or(DomDeserializeErrorKind::UnexpectedEof { expected })?;
         trace!(event = %event.trace(), kind = %"next");
         Ok(event)
     }
@@ -27,8 +27,8 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     ) -> Result<&DomEvent<'de>, DomDeserializeError<Self::Error>> {
         let event = self
             .peek_event()
-            .map_err(DomDeserializeError::Parser)?
-            .ok_or(DomDeserializeError::UnexpectedEof { expected })?;
+            .map_err(DomDeserializeErrorKind::Parser)?
+            .ok_or(DomDeserializeErrorKind::UnexpectedEof { expected })?;
         trace!(event = %event.trace(), kind = %"peek");
         Ok(event)
     }
@@ -37,10 +37,11 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     fn expect_node_start(&mut self) -> Result<Cow<'de, str>, DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("NodeStart")? {
             DomEvent::NodeStart { tag, .. } => Ok(tag),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "NodeStart",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -48,10 +49,11 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     fn expect_children_start(&mut self) -> Result<(), DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("ChildrenStart")? {
             DomEvent::ChildrenStart => Ok(()),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "ChildrenStart",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -59,10 +61,11 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     fn expect_children_end(&mut self) -> Result<(), DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("ChildrenEnd")? {
             DomEvent::ChildrenEnd => Ok(()),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "ChildrenEnd",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -70,21 +73,23 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     fn expect_node_end(&mut self) -> Result<(), DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("NodeEnd")? {
             DomEvent::NodeEnd => Ok(()),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "NodeEnd",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
-    /// Expect and consume a Text event, returning the text content.
+    /// Expect and consume a Text or CData event, returning the text content.
     fn expect_text(&mut self) -> Result<Cow<'de, str>, DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("Text")? {
-            DomEvent::Text(text) => Ok(text),
-            other => Err(DomDeserializeError::TypeMismatch {
+            DomEvent::Text(text) | DomEvent::CData(text) => Ok(text),
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "Text",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -102,10 +107,11 @@ pub trait DomParserExt<'de>: DomParser<'de> {
                 value,
                 namespace,
             }),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "Attribute",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 
@@ -113,10 +119,11 @@ pub trait DomParserExt<'de>: DomParser<'de> {
     fn expect_comment(&mut self) -> Result<Cow<'de, str>, DomDeserializeError<Self::Error>> {
         match self.next_event_or_eof("Comment")? {
             DomEvent::Comment(text) => Ok(text),
-            other => Err(DomDeserializeError::TypeMismatch {
+            other => Err(DomDeserializeErrorKind::TypeMismatch {
                 expected: "Comment",
                 got: format!("{other:?}"),
-            }),
+            }
+            .into()),
         }
     }
 }
@@ -0,0 +1,249 @@
+//! Element paths for locating deserialization errors in a document tree.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{DomEvent, DomParser, NameMatching, NamespaceMatching};
+
+/// A path from the document root to an element or attribute.
+///
+/// Displayed in an XPath-like form, e.g. `/feed/entry[3]/link[1]/@length`.
+/// Element indices are 1-based and count preceding siblings with the same tag;
+/// the root element is shown without one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementPath {
+    segments: Vec<PathSegment>,
+}
+
+/// A single step in an [`ElementPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A child element.
+    Element {
+        /// The element tag name.
+        tag: String,
+        /// 1-based position among siblings with the same tag.
+        index: usize,
+    },
+    /// An attribute of the preceding element.
+    Attribute(String),
+}
+
+impl ElementPath {
+    /// The segments of this path, from the root down.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Whether this path has no segments (the document itself).
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+impl fmt::Display for ElementPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return f.write_str("/");
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Element { tag, .. } if i == 0 => write!(f, "/{tag}")?,
+                PathSegment::Element { tag, index } => write!(f, "/{tag}[{index}]")?,
+                PathSegment::Attribute(name) => write!(f, "/@{name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One depth of the tracker's stack.
+///
+/// Frames are reused as the parser moves through the tree, so following a document
+/// allocates only when a depth or a tag is seen for the first time.
+#[derive(Default)]
+struct Frame {
+    /// Tag of the open element at this depth.
+    tag: String,
+    /// Index of the open element at this depth.
+    index: usize,
+    /// Bumped whenever a new parent opens, so stale `counts` read as zero.
+    generation: u64,
+    /// How many elements with each tag the current parent has had at this depth,
+    /// stamped with the generation they were counted in.
+    counts: HashMap<String, (u64, usize)>,
+}
+
+impl Frame {
+    /// Count a new element with `tag` at this depth and return its index.
+    fn count(&mut self, tag: &str) -> usize {
+        let generation = self.generation;
+        match self.counts.get_mut(tag) {
+            Some((seen, count)) => {
+                if *seen != generation {
+                    *seen = generation;
+                    *count = 0;
+                }
+                *count += 1;
+                *count
+            }
+            None => {
+                self.counts.insert(tag.to_owned(), (generation, 1));
+                1
+            }
+        }
+    }
+}
+
+/// The frame for elements at `depth`, created on first use.
+fn frame_at(frames: &mut Vec<Frame>, depth: usize) -> &mut Frame {
+    if frames.len() == depth {
+        frames.push(Frame::default());
+    }
+    &mut frames[depth]
+}
+
+/// A parser wrapper that follows consumed events to know where in the tree we are.
+///
+/// A closed element stays on the stack until the next event is peeked or consumed,
+/// so errors raised while finishing a value (e.g. a scalar that fails to parse, or
+/// a struct with missing fields) still point at the element that produced it.
+pub(crate) struct PathTracker<'de, P> {
+    inner: P,
+    /// One frame per depth seen so far; only the first `depth` are open.
+    frames: Vec<Frame>,
+    depth: usize,
+    attribute: Option<Cow<'de, str>>,
+    pending_pop: bool,
+}
+
+impl<'de, P> PathTracker<'de, P> {
+    pub(crate) fn new(inner: P) -> Self {
+        Self {
+            inner,
+            frames: Vec::new(),
+            depth: 0,
+            attribute: None,
+            pending_pop: false,
+        }
+    }
+
+    /// The path to the element (or attribute) currently being deserialized.
+    pub(crate) fn path(&self) -> ElementPath {
+        let mut segments: Vec<PathSegment> = self.frames[..self.depth]
+            .iter()
+            .map(|frame| PathSegment::Element {
+                tag: frame.tag.clone(),
+                index: frame.index,
+            })
+            .collect();
+        if let Some(name) = &self.attribute {
+            segments.push(PathSegment::Attribute(name.to_string()));
+        }
+        ElementPath { segments }
+    }
+
+    /// Drop state that only applies until the parser moves on.
+    fn settle(&mut self) {
+        if self.pending_pop {
+            self.depth -= 1;
+            self.pending_pop = false;
+        }
+        self.attribute = None;
+    }
+
+    fn open(&mut self, tag: &str) {
+        let frame = frame_at(&mut self.frames, self.depth);
+        frame.index = frame.count(tag);
+        frame.tag.clear();
+        frame.tag.push_str(tag);
+        self.depth += 1;
+        // Children of the new element start counting from scratch
+        if let Some(children) = self.frames.get_mut(self.depth) {
+            children.generation += 1;
+        }
+    }
+
+    fn observe(&mut self, event: &DomEvent<'de>) {
+        self.settle();
+        match event {
+            DomEvent::NodeStart { tag, .. } => self.open(tag),
+            DomEvent::Attribute { name, .. } => self.attribute = Some(name.clone()),
+            DomEvent::NodeEnd => self.pending_pop = true,
+            _ => {}
+        }
+    }
+}
+
+impl<'de, P: DomParser<'de>> DomParser<'de> for PathTracker<'de, P> {
+    type Error = P::Error;
+
+    fn next_event(&mut self) -> Result<Option<DomEvent<'de>>, Self::Error> {
+        let event = self.inner.next_event()?;
+        if let Some(event) = &event {
+            self.observe(event);
+        }
+        Ok(event)
+    }
+
+    fn peek_event(&mut self) -> Result<Option<&DomEvent<'de>>, Self::Error> {
+        self.settle();
+        self.inner.peek_event()
+    }
+
+    fn skip_node(&mut self) -> Result<(), Self::Error> {
+        self.settle();
+        // The skipped element still counts towards its siblings' indices
+        if let Some(DomEvent::NodeStart { tag, .. }) = self.inner.peek_event()? {
+            frame_at(&mut self.frames, self.depth).count(tag);
+        }
+        self.inner.skip_node()
+    }
+
+    fn current_span(&self) -> Option<facet_reflect::Span> {
+        self.inner.current_span()
+    }
+
+    fn location_at(&self, offset: usize) -> Option<crate::SourceLocation> {
+        self.inner.location_at(offset)
+    }
+
+    fn is_lenient(&self) -> bool {
+        self.inner.is_lenient()
+    }
+
+    fn deny_unknown_attributes(&self) -> Option<bool> {
+        self.inner.deny_unknown_attributes()
+    }
+
+    fn deny_unknown_elements(&self) -> Option<bool> {
+        self.inner.deny_unknown_elements()
+    }
+
+    fn name_matching(&self) -> NameMatching {
+        self.inner.name_matching()
+    }
+
+    fn namespace_matching(&self) -> NamespaceMatching {
+        self.inner.namespace_matching()
+    }
+
+    fn root_name(&self) -> Option<&str> {
+        self.inner.root_name()
+    }
+
+    fn format_namespace(&self) -> Option<&'static str> {
+        self.inner.format_namespace()
+    }
+
+    fn capture_raw_node(&mut self) -> Result<Option<std::borrow::Cow<'de, str>>, Self::Error> {
+        let raw = self.inner.capture_raw_node()?;
+        if raw.is_some() {
+            // The parser consumed through the element's NodeEnd
+            self.attribute = None;
+            self.pending_pop = true;
+        }
+        Ok(raw)
+    }
+}
@@ -66,11 +66,20 @@ pub trait DomSerializer {
     /// Emit text content.
     fn text(&mut self, content: &str) -> Result<(), Self::Error>;
 
-    /// Emit a comment (usually for debugging or special content).
+    /// Emit a comment.
+    ///
+    /// This is called for the values of fields marked with `#[facet(xml::comment)]`.
     fn comment(&mut self, _content: &str) -> Result<(), Self::Error> {
         Ok(())
     }
 
+    /// Emit a processing instruction.
+    ///
+    /// This is called for the values of fields marked with `#[facet(xml::pi)]`.
+    fn processing_instruction(&mut self, _target: &str, _data: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Emit a DOCTYPE declaration (XML/HTML).
     ///
     /// This is called before the root element when a field marked with
@@ -135,6 +144,30 @@ pub trait DomSerializer {
         false
     }
 
+    /// Check if the current field collects comments.
+    fn is_comment_field(&self) -> bool {
+        false
+    }
+
+    /// Check if the current field collects processing instructions.
+    fn is_processing_instruction_field(&self) -> bool {
+        false
+    }
+
+    /// Check if the current comment or processing instruction field holds the
+    /// items that go before the root element rather than among its children.
+    fn is_prolog_field(&self) -> bool {
+        false
+    }
+
+    /// Check if no element has been started yet, i.e. output is still in the prolog.
+    ///
+    /// The root element's prolog comments and processing instructions are
+    /// emitted before it when this returns true.
+    fn at_document_root(&self) -> bool {
+        false
+    }
+
     /// Clear field-related state after a field is serialized.
     fn clear_field_state(&mut self) {}
 
@@ -396,6 +429,22 @@ where
                 .map_err(DomSerializeError::Backend)?;
         }
 
+        // The root element's prolog comments and processing instructions go before it
+        if serializer.at_document_root() {
+            for (field_item, field_value) in &fields {
+                serializer
+                    .field_metadata(field_item)
+                    .map_err(DomSerializeError::Backend)?;
+                let is_comment = serializer.is_comment_field();
+                let is_pi = serializer.is_processing_instruction_field();
+                let is_prolog = serializer.is_prolog_field();
+                serializer.clear_field_state();
+                if (is_comment || is_pi) && is_prolog {
+                    serialize_markup(serializer, *field_value, is_comment)?;
+                }
+            }
+        }
+
         serializer
             .element_start(&tag, None)
             .map_err(DomSerializeError::Backend)?;
@@ -482,6 +531,18 @@ where
                 continue;
             }
 
+            // Comments and processing instructions are emitted in place; prolog
+            // ones were emitted before the root element, if this is the root
+            let is_comment = serializer.is_comment_field();
+            if is_comment || serializer.is_processing_instruction_field() {
+                let is_prolog = serializer.is_prolog_field();
+                serializer.clear_field_state();
+                if !is_prolog {
+                    serialize_markup(serializer, *field_value, is_comment)?;
+                }
+                continue;
+            }
+
             if serializer.is_text_field() {
                 if let Some(s) = value_to_string(*field_value, serializer) {
                     serializer.text(&s).map_err(DomSerializeError::Backend)?;
@@ -912,6 +973,17 @@ where
             continue;
         }
 
+        // Handle comments and processing instructions; a variant has no prolog
+        let is_comment = serializer.is_comment_field();
+        if is_comment || serializer.is_processing_instruction_field() {
+            let is_prolog = serializer.is_prolog_field();
+            serializer.clear_field_state();
+            if !is_prolog {
+                serialize_markup(serializer, *field_value, is_comment)?;
+            }
+            continue;
+        }
+
         // Handle text fields
         if serializer.is_text_field() {
             if let Some(s) = value_to_string(*field_value, serializer) {
@@ -976,6 +1048,73 @@ where
     Ok(())
 }
 
+/// Emit the comments or processing instructions held by a comment or PI field.
+///
+/// A processing instruction is either a struct with `target` and `data` fields,
+/// or a string whose first word is the target.
+fn serialize_markup<S>(
+    serializer: &mut S,
+    value: Peek<'_, '_>,
+    is_comment: bool,
+) -> Result<(), DomSerializeError<S::Error>>
+where
+    S: DomSerializer,
+{
+    let value = deref_if_pointer(value);
+
+    if let Ok(opt) = value.into_option() {
+        return match opt.value() {
+            Some(inner) => serialize_markup(serializer, inner, is_comment),
+            None => Ok(()),
+        };
+    }
+
+    if let Def::List(_) | Def::Array(_) | Def::Slice(_) = value.shape().def {
+        let list = value.into_list_like().map_err(DomSerializeError::Reflect)?;
+        for item in list.iter() {
+            serialize_markup(serializer, item, is_comment)?;
+        }
+        return Ok(());
+    }
+
+    if is_comment {
+        if let Some(text) = value_to_string(value, serializer) {
+            trace!(len = text.len(), "emitting comment");
+            serializer
+                .comment(&text)
+                .map_err(DomSerializeError::Backend)?;
+        }
+        return Ok(());
+    }
+
+    let (target, data) = if let Ok(struct_) = value.into_struct() {
+        let mut target = String::new();
+        let mut data = String::new();
+        for (field_item, field_value) in struct_.fields_for_serialize() {
+            match field_item.field.map(|f| f.name) {
+                Some("target") => {
+                    target = value_to_string(field_value, serializer).unwrap_or_default()
+                }
+                Some("data") => data = value_to_string(field_value, serializer).unwrap_or_default(),
+                _ => {}
+            }
+        }
+        (target, data)
+    } else if let Some(text) = value_to_string(value, serializer) {
+        match text.split_once(char::is_whitespace) {
+            Some((target, data)) => (target.to_string(), data.trim_start().to_string()),
+            None => (text, String::new()),
+        }
+    } else {
+        return Ok(());
+    };
+
+    trace!(%target, "emitting processing instruction");
+    serializer
+        .processing_instruction(&target, &data)
+        .map_err(DomSerializeError::Backend)
+}
+
 /// Serialize through a proxy type.
 fn serialize_via_proxy<S>(
     serializer: &mut S,
@@ -200,6 +200,19 @@ impl Element {
         }
     }
 
+    /// Serialize to the canonical form of a document with this element as the root.
+    ///
+    /// Attributes named `xmlns` or `xmlns:prefix` are namespace declarations.
+    pub fn canonicalize(
+        &self,
+        options: &xml::CanonicalOptions,
+    ) -> Result<Vec<u8>, xml::SerializeError<xml::XmlSerializeError>> {
+        xml::to_vec_with_options(
+            self,
+            &xml::SerializeOptions::new().canonical(options.clone()),
+        )
+    }
+
     /// Serialize to HTML string.
     pub fn to_html(&self) -> String {
         let mut out = String::new();
@@ -282,6 +295,27 @@ mod tests {
         assert_eq!(child.text_content(), "hello world");
     }
 
+    #[test]
+    fn canonicalize_element() {
+        let elem = Element::new("p:root")
+            .with_attr("xmlns:p", "urn:p")
+            .with_attr("xmlns:unused", "urn:u")
+            .with_attr("b", "2")
+            .with_attr("a", "1\n")
+            .with_child(Element::new("p:empty"))
+            .with_text("x\r\ny");
+
+        let options = facet_xml::CanonicalOptions::new().exclusive(true);
+        let out = String::from_utf8(elem.canonicalize(&options).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            "<p:root xmlns:p=\"urn:p\" a=\"1&#xA;\" b=\"2\"><p:empty></p:empty>x&#xD;\ny</p:root>"
+        );
+
+        let again = facet_xml::canonicalize(out.as_bytes(), &options).unwrap();
+        assert_eq!(again, out.as_bytes());
+    }
+
     #[test]
     fn parse_simple_xml() {
         let xml = r#"<root><child>hello</child></root>"#;
@@ -423,6 +457,21 @@ mod tests {
         assert_eq!(original, roundtripped);
     }
 
+    #[test]
+    fn roundtrip_preserves_whitespace_in_mixed_content() {
+        let xml = "<p>Hello <b>big</b> world <i> ! </i>\n</p>";
+        let options = facet_xml::DeserializeOptions::new().preserve_whitespace(true);
+        let elem: Element = facet_xml::from_str_with_options(xml, &options).unwrap();
+
+        assert_eq!(
+            elem.children[0],
+            Content::Text("Hello ".to_string()),
+            "leading text keeps its trailing space"
+        );
+        assert_eq!(elem.text_content(), "Hello big world  ! \n");
+        assert_eq!(facet_xml::to_string(&elem).unwrap(), xml);
+    }
+
     #[test]
     fn roundtrip_with_attrs() {
         #[derive(facet::Facet, Debug, PartialEq)]
@@ -0,0 +1,117 @@
+//! Owned vs. zero-copy deserialization.
+//!
+//! Run with `cargo bench -p facet-xml --bench borrowed`; the allocation
+//! columns show what borrowing from the input saves.
+//!
+//! - `owned` and `borrowed` deserialize the same catalog into owned and
+//!   borrowing types, both parsing the input in place.
+//! - `events_from_slice` reads the parser's events straight from the input, and
+//!   `events_from_reader` reads them from an I/O source, which copies each event
+//!   into the parser's buffer first.
+
+use std::borrow::Cow;
+
+use divan::{AllocProfiler, Bencher, black_box};
+use facet::Facet;
+use facet_dom::DomParser;
+use facet_xml::{self as xml, XmlParser};
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+#[derive(Facet)]
+struct Catalog<'a> {
+    #[facet(xml::elements)]
+    book: Vec<Book<'a>>,
+}
+
+#[derive(Facet)]
+struct Book<'a> {
+    #[facet(xml::attribute)]
+    id: &'a str,
+    #[facet(xml::attribute)]
+    lang: Cow<'a, str>,
+    title: &'a str,
+    author: &'a str,
+    summary: Cow<'a, str>,
+}
+
+#[derive(Facet)]
+#[facet(rename = "catalog")]
+struct OwnedCatalog {
+    #[facet(xml::elements)]
+    book: Vec<OwnedBook>,
+}
+
+#[derive(Facet)]
+#[facet(rename = "book")]
+struct OwnedBook {
+    #[facet(xml::attribute)]
+    id: String,
+    #[facet(xml::attribute)]
+    lang: String,
+    title: String,
+    author: String,
+    summary: String,
+}
+
+fn catalog(books: usize) -> String {
+    let mut xml = String::from("<catalog>");
+    for i in 0..books {
+        xml.push_str(&format!(
+            r#"<book id="bk{i}" lang="en"><title>Title {i}</title><author>Author {i}</author><summary>A summary of book number {i}, long enough to matter.</summary></book>"#
+        ));
+    }
+    xml.push_str("</catalog>");
+    xml
+}
+
+#[divan::bench(args = [10, 1000])]
+fn owned(bencher: Bencher, books: usize) {
+    let input = catalog(books);
+    bencher.bench(|| {
+        let catalog: OwnedCatalog = xml::from_str(black_box(&input)).unwrap();
+        black_box(catalog)
+    });
+}
+
+#[divan::bench(args = [10, 1000])]
+fn borrowed(bencher: Bencher, books: usize) {
+    let input = catalog(books);
+    bencher.bench(|| {
+        let catalog: Catalog<'_> = xml::from_str_borrowed(black_box(&input)).unwrap();
+        black_box(catalog)
+    });
+}
+
+/// Read every event, returning how many were borrowed from the input.
+fn drain<'de, P: DomParser<'de>>(mut parser: P) -> usize {
+    let mut borrowed = 0;
+    while let Some(event) = parser.next_event().unwrap() {
+        if let facet_dom::DomEvent::Text(Cow::Borrowed(_))
+        | facet_dom::DomEvent::NodeStart {
+            tag: Cow::Borrowed(_),
+            ..
+        } = event
+        {
+            borrowed += 1;
+        }
+    }
+    borrowed
+}
+
+#[divan::bench(args = [10, 1000])]
+fn events_from_slice(bencher: Bencher, books: usize) {
+    let input = catalog(books);
+    bencher.bench(|| drain(XmlParser::new(black_box(input.as_bytes()))));
+}
+
+#[divan::bench(args = [10, 1000])]
+fn events_from_reader(bencher: Bencher, books: usize) {
+    let input = catalog(books);
+    bencher.bench(|| drain(XmlParser::from_reader(black_box(input.as_bytes()))));
+}
@@ -0,0 +1,302 @@
+//! Deserialization from asynchronous sources.
+//!
+//! The deserializer pulls events synchronously, so it runs on a blocking task
+//! while the document is parsed with [`XmlParser::next_event_async`]. Events are
+//! handed over in batches through a bounded channel, so only a few batches are
+//! held in memory at any time, and parsing waits while the deserializer catches up.
+
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use facet_core::Facet;
+use facet_dom::{
+    DomDeserializer, DomEvent, DomParser, NameMatching, NamespaceMatching, SourceLocation,
+};
+use facet_reflect::Span;
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+use tokio::sync::mpsc;
+
+use crate::{DeserializeError, DeserializeOptions, XmlError, XmlParser};
+
+/// Number of events sent to the deserializer at once.
+const BATCH_SIZE: usize = 256;
+
+/// Number of batches that may wait for the deserializer.
+const BATCHES_IN_FLIGHT: usize = 2;
+
+/// Number of recently read events whose location is kept for errors.
+const RECENT_LOCATIONS: usize = 4;
+
+/// Size of the buffer input is read into.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Parse `reader` and deserialize it on a blocking task as the events arrive.
+pub(crate) async fn deserialize<T, R>(
+    reader: R,
+    options: &DeserializeOptions,
+) -> Result<T, DeserializeError<XmlError>>
+where
+    T: Facet<'static> + Send + 'static,
+    R: AsyncRead + Unpin,
+{
+    let (sender, receiver) = mpsc::channel(BATCHES_IN_FLIGHT);
+    let events = StreamedEvents::new(receiver, options.clone());
+    let task = tokio::task::spawn_blocking(move || {
+        let mut de = DomDeserializer::new_owned(events);
+        de.deserialize()
+    });
+
+    let mut parser =
+        XmlParser::from_async_buf_reader(LineTracker::new(reader)).with_options(options);
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    loop {
+        let event = parser.next_event_async().await;
+        let span = parser.current_span();
+        let location = span.and_then(|span| parser.get_mut().location(span.offset as usize));
+        let done = !matches!(event, Ok(Some(_)));
+        batch.push(Streamed {
+            event,
+            span,
+            location,
+        });
+        if done || batch.len() == BATCH_SIZE {
+            let full = mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+            // A closed channel means the deserializer has finished or failed
+            if sender.send(full).await.is_err() || done {
+                break;
+            }
+        }
+    }
+    drop(sender);
+    trace!("finished reading async input");
+
+    match task.await {
+        Ok(result) => result,
+        Err(e) => match e.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(e) => panic!("deserialization task did not finish: {e}"),
+        },
+    }
+}
+
+/// An event or the error that stopped parsing, with where it was read.
+struct Streamed {
+    event: Result<Option<DomEvent<'static>>, XmlError>,
+    span: Option<Span>,
+    location: Option<SourceLocation>,
+}
+
+/// Events received from the parsing task, replayed as a [`DomParser`].
+struct StreamedEvents {
+    receiver: mpsc::Receiver<Vec<Streamed>>,
+    /// The batch being replayed
+    batch: std::vec::IntoIter<Streamed>,
+    /// Buffer for peeked event
+    peeked: Option<DomEvent<'static>>,
+    /// Span of the most recently replayed event
+    span: Option<Span>,
+    /// Line and column of the last few events, by span offset
+    locations: VecDeque<(usize, SourceLocation)>,
+    /// Element depth, tracked for skip_node
+    depth: usize,
+    /// The parser's options, for the settings the deserializer asks about
+    options: DeserializeOptions,
+}
+
+impl StreamedEvents {
+    fn new(receiver: mpsc::Receiver<Vec<Streamed>>, options: DeserializeOptions) -> Self {
+        Self {
+            receiver,
+            batch: Vec::new().into_iter(),
+            peeked: None,
+            span: None,
+            locations: VecDeque::with_capacity(RECENT_LOCATIONS),
+            depth: 0,
+            options,
+        }
+    }
+
+    fn read_event(&mut self) -> Result<Option<DomEvent<'static>>, XmlError> {
+        let streamed = loop {
+            if let Some(streamed) = self.batch.next() {
+                break streamed;
+            }
+            match self.receiver.blocking_recv() {
+                Some(batch) => self.batch = batch.into_iter(),
+                // Parsing stopped after an error or the end of the document
+                None => return Ok(None),
+            }
+        };
+
+        self.span = streamed.span;
+        if let (Some(span), Some(location)) = (streamed.span, streamed.location) {
+            if self.locations.len() == RECENT_LOCATIONS {
+                self.locations.pop_front();
+            }
+            self.locations.push_back((span.offset as usize, location));
+        }
+        let event = streamed.event?;
+        match event {
+            Some(DomEvent::NodeStart { .. }) => self.depth += 1,
+            Some(DomEvent::NodeEnd) => self.depth -= 1,
+            _ => {}
+        }
+        Ok(event)
+    }
+}
+
+impl DomParser<'static> for StreamedEvents {
+    type Error = XmlError;
+
+    fn next_event(&mut self) -> Result<Option<DomEvent<'static>>, Self::Error> {
+        if let Some(event) = self.peeked.take() {
+            return Ok(Some(event));
+        }
+        self.read_event()
+    }
+
+    fn peek_event(&mut self) -> Result<Option<&DomEvent<'static>>, Self::Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_event()?;
+        }
+        Ok(self.peeked.as_ref())
+    }
+
+    fn skip_node(&mut self) -> Result<(), Self::Error> {
+        let start_depth = self.depth;
+
+        loop {
+            match self.next_event()? {
+                Some(DomEvent::NodeEnd) if self.depth < start_depth => break,
+                None => break,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn current_span(&self) -> Option<Span> {
+        self.span
+    }
+
+    fn location_at(&self, offset: usize) -> Option<SourceLocation> {
+        self.locations
+            .iter()
+            .rev()
+            .find(|(at, _)| *at == offset)
+            .map(|(_, location)| *location)
+    }
+
+    fn deny_unknown_attributes(&self) -> Option<bool> {
+        self.options.deny_unknown_attributes
+    }
+
+    fn deny_unknown_elements(&self) -> Option<bool> {
+        self.options.deny_unknown_elements
+    }
+
+    fn is_lenient(&self) -> bool {
+        self.options.lenient
+    }
+
+    fn name_matching(&self) -> NameMatching {
+        self.options.name_matching
+    }
+
+    fn namespace_matching(&self) -> NamespaceMatching {
+        self.options.namespace_matching
+    }
+
+    fn root_name(&self) -> Option<&str> {
+        self.options.root_name.as_deref()
+    }
+
+    fn format_namespace(&self) -> Option<&'static str> {
+        Some("xml")
+    }
+}
+
+/// Buffers an [`AsyncRead`] like tokio's `BufReader`, keeping just enough of
+/// the consumed input to find the line and column of later offsets.
+///
+/// Locations must be asked for in increasing order of offset; input before the
+/// last one asked for is dropped.
+struct LineTracker<R> {
+    inner: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+    /// Offset and location of the first byte of `consumed`
+    checkpoint: (usize, SourceLocation),
+    /// Input consumed by the parser since the checkpoint
+    consumed: VecDeque<u8>,
+}
+
+impl<R> LineTracker<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+            checkpoint: (0, SourceLocation { line: 1, column: 1 }),
+            consumed: VecDeque::new(),
+        }
+    }
+
+    /// The line and column of `offset`, or `None` if it is before an offset
+    /// already asked for.
+    fn location(&mut self, offset: usize) -> Option<SourceLocation> {
+        let (start, mut location) = self.checkpoint;
+        let skipped = offset.checked_sub(start)?.min(self.consumed.len());
+        // Count UTF-8 lead bytes so multi-byte characters occupy one column
+        for byte in self.consumed.drain(..skipped) {
+            if byte == b'\n' {
+                location.line += 1;
+                location.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                location.column += 1;
+            }
+        }
+        self.checkpoint = (start + skipped, location);
+        Some(location)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for LineTracker<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(out.remaining());
+        out.put_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncBufRead for LineTracker<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.pos == this.filled {
+            let mut buf = ReadBuf::new(&mut this.buf);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut buf))?;
+            this.filled = buf.filled().len();
+            this.pos = 0;
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..this.filled]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        let end = (this.pos + amt).min(this.filled);
+        this.consumed.extend(&this.buf[this.pos..end]);
+        this.pos = end;
+    }
+}
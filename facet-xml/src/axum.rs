@@ -21,6 +21,25 @@
 //!
 //! let app = Router::new().route("/person", post(create_person));
 //! ```
+//!
+//! The request body is parsed as it arrives, like [`from_async_reader`](crate::from_async_reader),
+//! instead of being collected into memory first.
+//!
+//! # Options
+//!
+//! The extractor picks up [`DeserializeOptions`] from the request extensions,
+//! so parser limits for untrusted input can be set with axum's `Extension` layer:
+//!
+//! ```ignore
+//! use axum::Extension;
+//! use facet_xml::{DeserializeOptions, ParserLimits};
+//!
+//! let options = DeserializeOptions::new()
+//!     .limits(ParserLimits::new().max_depth(64).max_elements(10_000));
+//! let app = Router::new()
+//!     .route("/person", post(create_person))
+//!     .layer(Extension(options));
+//! ```
 
 use axum_core::{
     body::Body,
@@ -29,11 +48,15 @@ use axum_core::{
 };
 use core::fmt;
 use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
 use facet_core::Facet;
 use http::{HeaderValue, StatusCode, header};
-use http_body_util::BodyExt;
+use http_body::Body as HttpBody;
+use std::io;
+use tokio::io::{AsyncRead, ReadBuf};
 
-use crate::{DeserializeError, XmlError};
+use crate::{DeserializeError, DeserializeOptions, XmlError};
 
 /// A wrapper type for XML-encoded request/response bodies.
 ///
@@ -133,24 +156,27 @@ impl IntoResponse for XmlRejection {
 
 impl<T, S> FromRequest<S> for Xml<T>
 where
-    T: Facet<'static>,
+    T: Facet<'static> + Send + 'static,
     S: Send + Sync,
 {
     type Rejection = XmlRejection;
 
     async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
-        // Read the body
-        let bytes = req
-            .into_body()
-            .collect()
-            .await
-            .map_err(|e| XmlRejection {
-                kind: XmlRejectionKind::Body(axum_core::Error::new(e)),
-            })?
-            .to_bytes();
-
-        // Deserialize
-        let value: T = crate::from_slice(&bytes).map_err(|e| XmlRejection {
+        let options = req
+            .extensions()
+            .get::<DeserializeOptions>()
+            .cloned()
+            .unwrap_or_default();
+
+        let mut body = BodyReader::new(req.into_body());
+        let result = crate::async_reader::deserialize(&mut body, &options).await;
+        // A body that failed to arrive is reported as such, not as malformed XML
+        if let Some(err) = body.error.take() {
+            return Err(XmlRejection {
+                kind: XmlRejectionKind::Body(err),
+            });
+        }
+        let value = result.map_err(|e| XmlRejection {
             kind: XmlRejectionKind::Deserialize(e),
         })?;
 
@@ -158,6 +184,53 @@ where
     }
 }
 
+/// Adapts a request body to [`AsyncRead`], keeping any error from the body.
+struct BodyReader {
+    body: Body,
+    /// Unread part of the current data frame
+    chunk: <Body as HttpBody>::Data,
+    error: Option<axum_core::Error>,
+}
+
+impl BodyReader {
+    fn new(body: Body) -> Self {
+        Self {
+            body,
+            chunk: Default::default(),
+            error: None,
+        }
+    }
+}
+
+impl AsyncRead for BodyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        while this.chunk.is_empty() {
+            match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                Some(Ok(frame)) => {
+                    // Trailers carry no data
+                    if let Ok(data) = frame.into_data() {
+                        this.chunk = data;
+                    }
+                }
+                Some(Err(e)) => {
+                    this.error = Some(e);
+                    return Poll::Ready(Err(io::Error::other("failed to read request body")));
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+        let n = this.chunk.len().min(buf.remaining());
+        buf.put_slice(&this.chunk[..n]);
+        this.chunk = this.chunk.slice(n..);
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl<T> IntoResponse for Xml<T>
 where
     T: Facet<'static>,
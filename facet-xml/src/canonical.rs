@@ -0,0 +1,573 @@
+//! Canonical XML 1.0 and Exclusive XML Canonicalization.
+//!
+//! Implements the canonical form of whole documents as specified by
+//! [Canonical XML 1.0](https://www.w3.org/TR/xml-c14n) and
+//! [Exclusive XML Canonicalization 1.0](https://www.w3.org/TR/xml-exc-c14n/).
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::dom_parser::{DeserializeOptions, XmlError};
+use crate::encoding;
+use crate::entities::{Entities, markup_declarations};
+
+/// The namespace bound to the `xml` prefix.
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Options for canonicalization.
+///
+/// The default is Canonical XML 1.0 without comments.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalOptions {
+    /// Whether to use Exclusive XML Canonicalization (default: false)
+    ///
+    /// Elements then only declare the namespaces they visibly use, so a
+    /// subtree's canonical form does not depend on its ancestors.
+    pub exclusive: bool,
+    /// Whether to keep comments (default: false)
+    pub with_comments: bool,
+    /// Prefixes handled as in inclusive canonicalization when exclusive,
+    /// the `InclusiveNamespaces PrefixList` (default: none)
+    ///
+    /// `#default` stands for the default namespace.
+    pub inclusive_prefixes: Vec<Cow<'static, str>>,
+}
+
+impl CanonicalOptions {
+    /// Create options for Canonical XML 1.0 without comments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use Exclusive XML Canonicalization.
+    pub const fn exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
+
+    /// Keep comments in the output.
+    pub const fn with_comments(mut self, with_comments: bool) -> Self {
+        self.with_comments = with_comments;
+        self
+    }
+
+    /// Set the prefixes handled as in inclusive canonicalization when exclusive.
+    pub fn inclusive_prefixes<P>(mut self, prefixes: impl IntoIterator<Item = P>) -> Self
+    where
+        P: Into<Cow<'static, str>>,
+    {
+        self.inclusive_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether `prefix` is in the inclusive prefix list; `""` is the default namespace.
+    fn is_inclusive(&self, prefix: &str) -> bool {
+        self.inclusive_prefixes.iter().any(|p| match &**p {
+            "#default" => prefix.is_empty(),
+            p => p == prefix,
+        })
+    }
+}
+
+/// Canonicalize an XML document.
+///
+/// The input encoding is detected as when deserializing, and the output is
+/// always UTF-8. Entity references are expanded and attribute defaults from
+/// the DTD internal subset are added; external entities are not loaded, so
+/// referencing one is an error, as is referencing an entity whose replacement
+/// text contains markup.
+///
+/// # Example
+///
+/// ```
+/// use facet_xml::{CanonicalOptions, canonicalize};
+///
+/// let input = br#"<?xml version="1.0"?>
+/// <doc b='2'   a="1"><empty/></doc>"#;
+/// let out = canonicalize(input, &CanonicalOptions::new()).unwrap();
+/// assert_eq!(out, br#"<doc a="1" b="2"><empty></empty></doc>"#);
+/// ```
+pub fn canonicalize(input: &[u8], options: &CanonicalOptions) -> Result<Vec<u8>, XmlError> {
+    let (input, _) = encoding::decode(input)?;
+    let mut reader = Reader::from_reader(input.as_ref());
+    reader.config_mut().trim_text(false);
+
+    let mut canonicalizer = Canonicalizer::new(options);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => canonicalizer.event(event)?,
+            Err(error) => return Err(XmlError::Parse(error.to_string())),
+        }
+    }
+    if canonicalizer.depth > 0 {
+        return Err(XmlError::UnexpectedEof);
+    }
+    Ok(canonicalizer.out.into_bytes())
+}
+
+/// An attribute declared in an `ATTLIST`.
+struct AttributeDecl {
+    name: String,
+    /// Whether the type is anything but `CDATA`, which normalizes spaces further
+    tokenized: bool,
+    /// Default value, unnormalized
+    default: Option<String>,
+}
+
+/// Where the parser is relative to the document element.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Prolog,
+    Root,
+    Epilog,
+}
+
+struct Canonicalizer<'a> {
+    options: &'a CanonicalOptions,
+    out: String,
+    entities: Entities,
+    /// Options for expanding entities: unknown ones cannot be canonicalized
+    entity_options: DeserializeOptions,
+    /// Attribute declarations from the DTD, by element name
+    attributes: HashMap<String, Vec<AttributeDecl>>,
+    /// Namespace declarations (prefix, URI) on each open element in the input;
+    /// the empty prefix is the default namespace
+    declared: Vec<Vec<(String, String)>>,
+    /// Namespace declarations written on each open element
+    rendered: Vec<Vec<(String, String)>>,
+    depth: usize,
+    position: Position,
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn new(options: &'a CanonicalOptions) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            entities: Entities::default(),
+            entity_options: DeserializeOptions::new().deny_unknown_entities(true),
+            attributes: HashMap::new(),
+            declared: Vec::new(),
+            rendered: Vec::new(),
+            depth: 0,
+            position: Position::Prolog,
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>) -> Result<(), XmlError> {
+        match event {
+            Event::Start(e) => self.start(&e, false)?,
+            Event::Empty(e) => self.start(&e, true)?,
+            Event::End(e) => {
+                let name = utf8(e.name().into_inner())?;
+                self.end(name);
+            }
+            Event::Text(e) if self.position == Position::Root => {
+                let text = utf8(&e)?;
+                escape_text(&normalize_line_ends(text), &mut self.out);
+            }
+            Event::GeneralRef(e) if self.position == Position::Root => {
+                let name = utf8(&e)?;
+                let mut text = String::new();
+                // Replacement text is only canonicalized as character data
+                self.entities
+                    .resolve_text(name, &mut text, &self.entity_options)?;
+                escape_text(&text, &mut self.out);
+            }
+            Event::CData(e) => {
+                let text = utf8(&e)?;
+                escape_text(&normalize_line_ends(text), &mut self.out);
+            }
+            Event::Comment(e) if self.options.with_comments => {
+                let text = normalize_line_ends(utf8(&e)?);
+                self.markup(|out| {
+                    out.push_str("<!--");
+                    out.push_str(&text);
+                    out.push_str("-->");
+                });
+            }
+            Event::PI(e) => {
+                let content = normalize_line_ends(utf8(&e)?);
+                let (target, data) = content
+                    .split_once(char::is_whitespace)
+                    .map_or((&*content, ""), |(target, data)| {
+                        (target, data.trim_start())
+                    });
+                self.markup(|out| {
+                    out.push_str("<?");
+                    out.push_str(target);
+                    if !data.is_empty() {
+                        out.push(' ');
+                        out.push_str(data);
+                    }
+                    out.push_str("?>");
+                });
+            }
+            Event::DocType(e) => {
+                let doctype = utf8(&e)?;
+                self.entities.declare_from_doctype(doctype);
+                self.declare_attributes(doctype);
+            }
+            // The XML declaration, and whitespace or references outside the document element
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Write a comment or processing instruction, separating it from the
+    /// document element with a line feed when outside of it.
+    fn markup(&mut self, write: impl FnOnce(&mut String)) {
+        if self.position == Position::Epilog {
+            self.out.push('\n');
+        }
+        write(&mut self.out);
+        if self.position == Position::Prolog {
+            self.out.push('\n');
+        }
+    }
+
+    fn start(&mut self, e: &BytesStart<'_>, is_empty: bool) -> Result<(), XmlError> {
+        let name = utf8(e.name().into_inner())?;
+
+        let mut declarations = Vec::new();
+        let mut attributes = Vec::new();
+        for attr in e.attributes() {
+            let attr = attr.map_err(|e| XmlError::Parse(e.to_string()))?;
+            let key = utf8(attr.key.into_inner())?;
+            let value = self.attribute_value(utf8(&attr.value)?)?;
+            if key == "xmlns" {
+                declarations.push((String::new(), value));
+            } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                declarations.push((prefix.to_string(), value));
+            } else {
+                attributes.push((key.to_string(), value));
+            }
+        }
+        self.apply_attribute_decls(name, &mut attributes)?;
+        self.declared.push(declarations);
+
+        let namespaces = self.namespaces_to_render(name, &attributes)?;
+
+        let mut sorted = Vec::with_capacity(attributes.len());
+        for (key, value) in attributes {
+            let (namespace, local) = match key.split_once(':') {
+                Some((prefix, local)) => (self.lookup(prefix)?, local.to_string()),
+                None => (String::new(), key.clone()),
+            };
+            sorted.push(((namespace, local), key, value));
+        }
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        self.out.push('<');
+        self.out.push_str(name);
+        for (prefix, uri) in &namespaces {
+            self.out.push_str(" xmlns");
+            if !prefix.is_empty() {
+                self.out.push(':');
+                self.out.push_str(prefix);
+            }
+            self.out.push_str("=\"");
+            escape_attribute(uri, &mut self.out);
+            self.out.push('"');
+        }
+        for (_, key, value) in &sorted {
+            self.out.push(' ');
+            self.out.push_str(key);
+            self.out.push_str("=\"");
+            escape_attribute(value, &mut self.out);
+            self.out.push('"');
+        }
+        self.out.push('>');
+
+        self.rendered.push(namespaces);
+        self.depth += 1;
+        self.position = Position::Root;
+        if is_empty {
+            self.end(name);
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, name: &str) {
+        self.out.push_str("</");
+        self.out.push_str(name);
+        self.out.push('>');
+        self.declared.pop();
+        self.rendered.pop();
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            self.position = Position::Epilog;
+        }
+    }
+
+    /// The namespace declarations to write on an element, sorted by prefix
+    /// with the default namespace first.
+    ///
+    /// A declaration is written when the nearest one written by an ancestor
+    /// binds the prefix differently. Inclusive canonicalization considers the
+    /// element's own declarations; exclusive canonicalization considers the
+    /// prefixes the element and its attributes use, plus the inclusive prefixes.
+    fn namespaces_to_render(
+        &self,
+        name: &str,
+        attributes: &[(String, String)],
+    ) -> Result<Vec<(String, String)>, XmlError> {
+        let mut candidates: Vec<String> = Vec::new();
+        if self.options.exclusive {
+            let element_prefix = name.split_once(':').map_or("", |(prefix, _)| prefix);
+            candidates.push(element_prefix.to_string());
+            candidates.extend(
+                attributes
+                    .iter()
+                    .filter_map(|(key, _)| key.split_once(':'))
+                    .map(|(prefix, _)| prefix.to_string()),
+            );
+            candidates.extend(
+                self.declared
+                    .iter()
+                    .flatten()
+                    .filter(|(prefix, _)| self.options.is_inclusive(prefix))
+                    .map(|(prefix, _)| prefix.clone()),
+            );
+        } else if let Some(own) = self.declared.last() {
+            candidates.extend(own.iter().map(|(prefix, _)| prefix.clone()));
+        }
+        candidates.sort();
+        candidates.dedup();
+
+        let mut namespaces = Vec::new();
+        for prefix in candidates {
+            if prefix == "xml" {
+                continue;
+            }
+            let uri = self.lookup(&prefix)?;
+            let rendered = self.rendered_uri(&prefix);
+            let superfluous = match rendered {
+                Some(rendered) => rendered == uri,
+                // An undeclared default namespace is the empty one
+                None => prefix.is_empty() && uri.is_empty(),
+            };
+            if !superfluous {
+                namespaces.push((prefix, uri));
+            }
+        }
+        Ok(namespaces)
+    }
+
+    /// The namespace URI bound to `prefix` in the input, `""` for no default namespace.
+    fn lookup(&self, prefix: &str) -> Result<String, XmlError> {
+        if prefix == "xml" {
+            return Ok(XML_NAMESPACE.to_string());
+        }
+        let uri = self
+            .declared
+            .iter()
+            .rev()
+            .flat_map(|frame| frame.iter().rev())
+            .find(|(declared, _)| declared == prefix)
+            .map(|(_, uri)| uri.clone());
+        match uri {
+            Some(uri) => Ok(uri),
+            None if prefix.is_empty() => Ok(String::new()),
+            None => Err(XmlError::UnboundPrefix {
+                prefix: prefix.to_string(),
+            }),
+        }
+    }
+
+    /// The URI bound to `prefix` by the nearest declaration written on an
+    /// open element, excluding the current one.
+    fn rendered_uri(&self, prefix: &str) -> Option<&str> {
+        self.rendered
+            .iter()
+            .rev()
+            .flat_map(|frame| frame.iter())
+            .find(|(rendered, _)| rendered == prefix)
+            .map(|(_, uri)| uri.as_str())
+    }
+
+    /// Normalize an attribute value: line ends and literal whitespace become
+    /// spaces, and references are expanded.
+    fn attribute_value(&mut self, raw: &str) -> Result<String, XmlError> {
+        let raw = normalize_line_ends(raw);
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = &*raw;
+        while let Some(amp) = rest.find('&') {
+            push_whitespace_normalized(&rest[..amp], &mut out);
+            let semi = rest[amp..]
+                .find(';')
+                .ok_or_else(|| XmlError::Parse("unterminated reference".to_string()))?;
+            let name = &rest[amp + 1..amp + semi];
+            let mut expanded = String::new();
+            self.entities
+                .resolve(name, &mut expanded, &self.entity_options)?;
+            if name.starts_with('#') {
+                // Characters from character references are kept as they are
+                out.push_str(&expanded);
+            } else {
+                push_whitespace_normalized(&expanded, &mut out);
+            }
+            rest = &rest[amp + semi + 1..];
+        }
+        push_whitespace_normalized(rest, &mut out);
+        Ok(out)
+    }
+
+    /// Normalize attributes of non-`CDATA` types and add defaulted attributes.
+    fn apply_attribute_decls(
+        &mut self,
+        element: &str,
+        attributes: &mut Vec<(String, String)>,
+    ) -> Result<(), XmlError> {
+        let Some(decls) = self.attributes.get(element) else {
+            return Ok(());
+        };
+        let mut defaults = Vec::new();
+        for decl in decls {
+            match attributes.iter_mut().find(|(key, _)| *key == decl.name) {
+                Some((_, value)) if decl.tokenized => *value = collapse_spaces(value),
+                Some(_) => {}
+                None => {
+                    if let Some(default) = &decl.default {
+                        defaults.push((decl.name.clone(), default.clone(), decl.tokenized));
+                    }
+                }
+            }
+        }
+        for (name, default, tokenized) in defaults {
+            let mut value = self.attribute_value(&default)?;
+            if tokenized {
+                value = collapse_spaces(&value);
+            }
+            attributes.push((name, value));
+        }
+        Ok(())
+    }
+
+    /// Record the `ATTLIST` declarations of a DOCTYPE's internal subset.
+    ///
+    /// The first declaration of an attribute wins.
+    fn declare_attributes(&mut self, doctype: &str) {
+        for decl in markup_declarations(doctype) {
+            let Some(rest) = decl.strip_prefix("ATTLIST") else {
+                continue;
+            };
+            let mut tokens = DeclTokens(rest);
+            let Some(element) = tokens.next() else {
+                continue;
+            };
+            let decls = self.attributes.entry(element.to_string()).or_default();
+            while let (Some(name), Some(kind)) = (tokens.next(), tokens.next()) {
+                // NOTATION is followed by the enumeration of notations
+                if kind == "NOTATION" {
+                    tokens.next();
+                }
+                let default = match tokens.next() {
+                    Some("#FIXED") => tokens.next(),
+                    Some("#REQUIRED" | "#IMPLIED") | None => None,
+                    Some(literal) => Some(literal),
+                }
+                .and_then(unquote);
+                if decls.iter().all(|d| d.name != name) {
+                    decls.push(AttributeDecl {
+                        name: name.to_string(),
+                        tokenized: kind != "CDATA",
+                        default: default.map(str::to_string),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Splits the body of an `ATTLIST` declaration into names, parenthesized
+/// enumerations and quoted literals.
+struct DeclTokens<'a>(&'a str);
+
+impl<'a> Iterator for DeclTokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.0.trim_start();
+        let end = match rest.chars().next()? {
+            quote @ ('"' | '\'') => rest[1..].find(quote).map_or(rest.len(), |i| i + 2),
+            '(' => rest.find(')').map_or(rest.len(), |i| i + 1),
+            _ => rest
+                .find(|c: char| c.is_whitespace() || c == '(')
+                .unwrap_or(rest.len()),
+        };
+        let (token, after) = rest.split_at(end);
+        self.0 = after;
+        Some(token)
+    }
+}
+
+/// The content of a quoted literal.
+fn unquote(literal: &str) -> Option<&str> {
+    let quote = literal.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    literal[1..].strip_suffix(quote)
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, XmlError> {
+    core::str::from_utf8(bytes).map_err(XmlError::InvalidUtf8)
+}
+
+/// Replace `\r\n` and lone `\r` with `\n`.
+fn normalize_line_ends(text: &str) -> Cow<'_, str> {
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Append `text` with each whitespace character replaced by a space.
+fn push_whitespace_normalized(text: &str, out: &mut String) {
+    out.extend(text.chars().map(|c| {
+        if matches!(c, '\t' | '\n' | '\r') {
+            ' '
+        } else {
+            c
+        }
+    }));
+}
+
+/// Drop leading and trailing spaces and collapse runs of them, as for
+/// attributes of non-`CDATA` types.
+fn collapse_spaces(value: &str) -> String {
+    value
+        .split(' ')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn escape_text(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn escape_attribute(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' => out.push_str("&#x9;"),
+            '\n' => out.push_str("&#xA;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
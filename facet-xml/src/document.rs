@@ -0,0 +1,147 @@
+//! Writing documents item by item.
+
+use std::borrow::Cow;
+use std::io::Write;
+
+use facet_core::Facet;
+use facet_dom::{DomSerializeError, DomSerializer};
+use facet_reflect::Peek;
+
+use crate::serializer::{SerializeOptions, XmlSerializeError, XmlSerializer};
+
+/// The root element opened by an [`XmlDocumentWriter`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RootElement {
+    /// The element name
+    pub name: Cow<'static, str>,
+    /// Default namespace declared with `xmlns="..."`
+    pub default_namespace: Option<Cow<'static, str>>,
+    /// Prefixed namespaces (prefix, URI) declared with `xmlns:prefix="..."`
+    pub namespaces: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+    /// Attributes (name, value)
+    pub attributes: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+}
+
+impl RootElement {
+    /// Create a root element with the given name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Declare the default namespace.
+    ///
+    /// Items in this namespace, e.g. with `xml::ns_all`, are written unprefixed.
+    pub fn default_namespace(mut self, uri: impl Into<Cow<'static, str>>) -> Self {
+        self.default_namespace = Some(uri.into());
+        self
+    }
+
+    /// Declare a prefixed namespace.
+    ///
+    /// Items using this namespace use the prefix and do not declare it again.
+    pub fn namespace(
+        mut self,
+        prefix: impl Into<Cow<'static, str>>,
+        uri: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    /// Add an attribute.
+    pub fn attribute(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Writes a document one child of the root element at a time.
+///
+/// This produces large documents, like sitemaps or feeds, without holding all
+/// of their items in memory. Output goes to the writer through an internal buffer.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::{RootElement, XmlDocumentWriter};
+///
+/// #[derive(Facet)]
+/// #[facet(rename = "url")]
+/// struct Url {
+///     loc: String,
+/// }
+///
+/// let root = RootElement::new("urlset")
+///     .default_namespace("http://www.sitemaps.org/schemas/sitemap/0.9");
+/// let mut doc = XmlDocumentWriter::open(Vec::new(), &root).unwrap();
+/// for page in ["a", "b"] {
+///     doc.write_item(&Url { loc: format!("https://example.com/{page}") }).unwrap();
+/// }
+/// let out = doc.close().unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>https://example.com/a</loc></url><url><loc>https://example.com/b</loc></url></urlset>"#
+/// );
+/// ```
+pub struct XmlDocumentWriter<W: Write> {
+    serializer: XmlSerializer<W>,
+    /// Name of the root element, for closing it
+    root_name: Cow<'static, str>,
+}
+
+impl<W: Write> XmlDocumentWriter<W> {
+    /// Open a document on `writer` with default options, writing the root start tag.
+    pub fn open(
+        writer: W,
+        root: &RootElement,
+    ) -> Result<Self, DomSerializeError<XmlSerializeError>> {
+        Self::open_with_options(writer, root, SerializeOptions::default())
+    }
+
+    /// Open a document on `writer` with custom options, writing the XML
+    /// declaration if configured and the root start tag.
+    pub fn open_with_options(
+        writer: W,
+        root: &RootElement,
+        options: SerializeOptions,
+    ) -> Result<Self, DomSerializeError<XmlSerializeError>> {
+        let mut serializer = XmlSerializer::from_writer_with_options(writer, options);
+        serializer
+            .open_root(root)
+            .map_err(DomSerializeError::Backend)?;
+        Ok(Self {
+            serializer,
+            root_name: root.name.clone(),
+        })
+    }
+
+    /// Write a value as the next child of the root element.
+    pub fn write_item<'facet, T>(
+        &mut self,
+        item: &'_ T,
+    ) -> Result<(), DomSerializeError<XmlSerializeError>>
+    where
+        T: Facet<'facet> + ?Sized,
+    {
+        facet_dom::serialize(&mut self.serializer, Peek::new(item))
+    }
+
+    /// Close the root element, flush the output and return the writer.
+    pub fn close(mut self) -> Result<W, DomSerializeError<XmlSerializeError>> {
+        self.serializer
+            .element_end(&self.root_name)
+            .map_err(DomSerializeError::Backend)?;
+        self.serializer
+            .into_inner()
+            .map_err(DomSerializeError::Backend)
+    }
+}
@@ -6,13 +6,18 @@ use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
-use std::io::Cursor;
+use std::io::{BufRead, BufReader, Cursor, Read};
 
-use facet_dom::{DomEvent, DomParser};
+use crate::encoding::{self, Encoding, SliceInput};
+use crate::entities::Entities;
+
+use facet_dom::{DomEvent, DomParser, NameMatching, NamespaceMatching, SourceLocation};
+use facet_reflect::Span;
 use quick_xml::NsReader;
-use quick_xml::escape::resolve_xml_entity;
+use quick_xml::errors::IllFormedError;
 use quick_xml::events::Event;
-use quick_xml::name::ResolveResult;
+use quick_xml::name::{NamespaceResolver, ResolveResult};
+use quick_xml::reader::Config;
 
 /// XML parsing error.
 #[derive(Debug, Clone)]
@@ -21,10 +26,39 @@ pub enum XmlError {
     Parse(String),
     /// Unexpected end of input.
     UnexpectedEof,
-    /// Unbalanced tags.
-    UnbalancedTags,
+    /// An end tag that does not match the open element, or an element left open
+    /// at the end of input.
+    ///
+    /// Only raised when [`DeserializeOptions::strict_namespaces`] is set; otherwise
+    /// mismatched end tags are reported as [`XmlError::Parse`].
+    UnbalancedTags {
+        /// Name of the open element that should have been closed, if any
+        expected: Option<String>,
+        /// Name of the end tag found, or `None` at the end of input
+        found: Option<String>,
+    },
+    /// An element or attribute name uses a namespace prefix with no `xmlns:` declaration in scope.
+    ///
+    /// Only raised when [`DeserializeOptions::strict_namespaces`] is set.
+    UnboundPrefix {
+        /// The undeclared prefix
+        prefix: String,
+    },
     /// Invalid UTF-8.
     InvalidUtf8(core::str::Utf8Error),
+    /// The XML declaration names an encoding that is not supported.
+    UnsupportedEncoding(String),
+    /// Reference to an entity that is neither predefined nor declared in the DTD.
+    ///
+    /// Only raised when [`DeserializeOptions::deny_unknown_entities`] is set.
+    UnknownEntity(String),
+    /// A configured [`ParserLimits`] limit was exceeded.
+    LimitExceeded {
+        /// Which limit was hit.
+        limit: LimitKind,
+        /// The configured maximum.
+        max: usize,
+    },
 }
 
 impl fmt::Display for XmlError {
@@ -32,19 +66,183 @@ impl fmt::Display for XmlError {
         match self {
             XmlError::Parse(msg) => write!(f, "XML parse error: {}", msg),
             XmlError::UnexpectedEof => write!(f, "Unexpected end of XML"),
-            XmlError::UnbalancedTags => write!(f, "Unbalanced XML tags"),
+            XmlError::UnbalancedTags { expected, found } => match (expected, found) {
+                (Some(expected), Some(found)) => write!(
+                    f,
+                    "Unbalanced XML tags: expected </{}>, found </{}>",
+                    expected, found
+                ),
+                (None, Some(found)) => write!(f, "Unbalanced XML tags: unexpected </{}>", found),
+                (Some(expected), None) => {
+                    write!(f, "Unbalanced XML tags: <{}> is never closed", expected)
+                }
+                (None, None) => write!(f, "Unbalanced XML tags"),
+            },
+            XmlError::UnboundPrefix { prefix } => {
+                write!(f, "Undeclared namespace prefix: {}", prefix)
+            }
             XmlError::InvalidUtf8(e) => write!(f, "Invalid UTF-8 in XML: {}", e),
+            XmlError::UnsupportedEncoding(label) => {
+                write!(f, "Unsupported XML encoding: {}", label)
+            }
+            XmlError::UnknownEntity(name) => write!(f, "Unknown entity: &{};", name),
+            XmlError::LimitExceeded { limit, max } => {
+                write!(f, "XML {} limit exceeded (max {})", limit, max)
+            }
         }
     }
 }
 
 impl std::error::Error for XmlError {}
 
+/// A resource limit enforced by [`ParserLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitKind {
+    /// Element nesting depth.
+    Depth,
+    /// Number of attributes on a single element.
+    Attributes,
+    /// Size in bytes of a single text node.
+    TextSize,
+    /// Total number of events produced by the parser.
+    Events,
+    /// Total number of elements in the document.
+    Elements,
+    /// Nesting depth of entity references inside declared entities.
+    EntityDepth,
+    /// Total size in bytes produced by expanding declared entities.
+    EntityExpansion,
+}
+
+impl fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LimitKind::Depth => "nesting depth",
+            LimitKind::Attributes => "attribute count",
+            LimitKind::TextSize => "text size",
+            LimitKind::Events => "event count",
+            LimitKind::Elements => "element count",
+            LimitKind::EntityDepth => "entity nesting depth",
+            LimitKind::EntityExpansion => "entity expansion size",
+        })
+    }
+}
+
+/// Resource limits for parsing untrusted XML.
+///
+/// Every limit is disabled (`None`) by default. When a limit is exceeded,
+/// parsing stops with [`XmlError::LimitExceeded`].
+///
+/// # Example
+///
+/// ```
+/// use facet_xml::{DeserializeOptions, ParserLimits};
+///
+/// let options = DeserializeOptions::new().limits(
+///     ParserLimits::new()
+///         .max_depth(32)
+///         .max_attributes(64)
+///         .max_text_size(1 << 20)
+///         .max_elements(100_000),
+/// );
+/// # let _ = options;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// Maximum element nesting depth (the root element is at depth 1)
+    pub max_depth: Option<usize>,
+    /// Maximum number of attributes on a single element, including namespace declarations
+    pub max_attributes: Option<usize>,
+    /// Maximum size in bytes of a single text node, after entity expansion
+    pub max_text_size: Option<usize>,
+    /// Maximum total number of events (elements, attributes, text, ...) produced
+    pub max_events: Option<usize>,
+    /// Maximum total number of elements in the document
+    pub max_elements: Option<usize>,
+}
+
+impl ParserLimits {
+    /// Create limits with every limit disabled.
+    pub const fn new() -> Self {
+        Self {
+            max_depth: None,
+            max_attributes: None,
+            max_text_size: None,
+            max_events: None,
+            max_elements: None,
+        }
+    }
+
+    /// Limit element nesting depth.
+    pub const fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Limit the number of attributes on a single element.
+    pub const fn max_attributes(mut self, max: usize) -> Self {
+        self.max_attributes = Some(max);
+        self
+    }
+
+    /// Limit the size in bytes of a single text node.
+    pub const fn max_text_size(mut self, max: usize) -> Self {
+        self.max_text_size = Some(max);
+        self
+    }
+
+    /// Limit the total number of events produced by the parser.
+    pub const fn max_events(mut self, max: usize) -> Self {
+        self.max_events = Some(max);
+        self
+    }
+
+    /// Limit the total number of elements in the document.
+    pub const fn max_elements(mut self, max: usize) -> Self {
+        self.max_elements = Some(max);
+        self
+    }
+
+    /// Fail with [`XmlError::LimitExceeded`] if `value` is over the limit.
+    fn check(limit: Option<usize>, kind: LimitKind, value: usize) -> Result<(), XmlError> {
+        match limit {
+            Some(max) if value > max => Err(XmlError::LimitExceeded { limit: kind, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Streaming XML parser implementing `DomParser`.
-pub struct XmlParser<'de> {
-    reader: NsReader<Cursor<&'de [u8]>>,
-    /// Original input for raw capture
-    input: &'de [u8],
+///
+/// By default the parser reads from an in-memory slice. The slice may be UTF-8,
+/// UTF-16 (with a byte order mark), or any encoding named in the XML declaration
+/// that [`Encoding`] supports; it is transcoded to UTF-8 before parsing, and
+/// [`XmlParser::encoding`] reports what was detected. Use
+/// [`XmlParser::from_reader`] or [`XmlParser::from_buf_reader`] to parse from
+/// an I/O source without loading it into memory first. In that mode, raw
+/// capture (for [`facet_dom::RawMarkup`]) is not supported and errors report
+/// byte offsets instead of line/column positions, and the input must be UTF-8.
+/// With the `tokio` feature, [`XmlParser::from_async_reader`] does the same
+/// for asynchronous sources.
+///
+/// Spans of UTF-8 input are byte offsets into the slice as given, byte order mark
+/// included. Spans of transcoded input are byte offsets into the UTF-8 text it
+/// was converted to, so they do not index the original bytes; line and column
+/// positions count characters and are right either way.
+pub struct XmlParser<'de, R = Cursor<SliceInput<'de>>> {
+    reader: Source<'de, R>,
+    /// UTF-8 input for raw capture, when parsing from a slice
+    input: Option<SliceInput<'de>>,
+    /// The input, when events point into it and it is valid UTF-8, to borrow from
+    borrowable: Option<&'de str>,
+    /// Encoding the input was transcoded from
+    encoding: Encoding,
+    /// Length of the byte order mark dropped from UTF-8 input, added to spans so
+    /// they index the caller's bytes
+    bom_len: usize,
+    /// Error from decoding the input, reported on the first read
+    decode_error: Option<XmlError>,
     /// Buffer for quick-xml events
     buf: Vec<u8>,
     /// Buffer for peeked event
@@ -52,7 +250,7 @@ pub struct XmlParser<'de> {
     /// Stack tracking element depth for skip_node
     depth: usize,
     /// Pending attributes from the current element
-    pending_attrs: Vec<(Option<String>, String, String)>,
+    pending_attrs: Vec<(Option<String>, Cow<'de, str>, Cow<'de, str>)>,
     /// Index into pending_attrs
     attr_idx: usize,
     /// State machine for event generation
@@ -61,6 +259,237 @@ pub struct XmlParser<'de> {
     is_empty_element: bool,
     /// Position where current node started (for raw capture)
     node_start_pos: u64,
+    /// Source span of the most recently read XML event
+    span: Option<Span>,
+    /// Parser configuration
+    options: DeserializeOptions,
+    /// Entities declared in the DOCTYPE's internal subset
+    entities: Entities,
+    /// Whether whitespace is preserved in each open element (from `xml:space`)
+    space_stack: Vec<bool>,
+    /// Text and entity references read so far, merged into a single Text event
+    text_buf: String,
+    /// Pending text borrowed from the input, while it is a single unescaped piece
+    text_borrowed: Option<&'de str>,
+    /// Source span covering `text_buf`
+    text_span: Option<Span>,
+    /// Whether `text_buf` is emitted verbatim rather than trimmed
+    text_preserve: bool,
+    /// Event read after pending text, emitted once the text has been
+    queued: Option<(DomEvent<'de>, Option<Span>)>,
+    /// Number of events produced so far
+    event_count: usize,
+    /// Number of elements seen so far
+    element_count: usize,
+    /// Qualified names of the open elements, tracked in strict mode only
+    open_tags: Vec<String>,
+}
+
+/// Options for XML deserialization.
+#[derive(Debug, Clone)]
+pub struct DeserializeOptions {
+    /// Whether to keep whitespace in text content exactly as written (default: false)
+    ///
+    /// By default, leading and trailing whitespace is trimmed from text and
+    /// whitespace-only text is dropped, except inside elements marked with
+    /// `xml:space="preserve"`. When `true`, all text inside the root element
+    /// is emitted verbatim. `xml:space="default"` reverts to this setting.
+    pub preserve_whitespace: bool,
+    /// Resource limits for the parser (default: no limits)
+    pub limits: ParserLimits,
+    /// Whether a reference to an undeclared entity is an error (default: false)
+    ///
+    /// By default such references are kept in the text as written, e.g. `&name;`.
+    pub deny_unknown_entities: bool,
+    /// Maximum nesting of references inside entities declared in the DTD (default: 16)
+    pub max_entity_depth: usize,
+    /// Maximum total size in bytes produced by expanding entities declared in
+    /// the DTD, across the whole document (default: 1 MiB)
+    ///
+    /// Each reference to a declared entity also counts the length of the
+    /// reference itself, so entities that expand to nothing still use up the
+    /// budget. Together with `max_entity_depth`, this rejects "billion laughs" input.
+    pub max_entity_expansion: usize,
+    /// Whether to reject undeclared namespace prefixes and unbalanced tags (default: false)
+    ///
+    /// By default an element or attribute with an unbound prefix, like `<soap:Body>`
+    /// without `xmlns:soap`, is treated as having no namespace. When `true`, it
+    /// fails with [`XmlError::UnboundPrefix`], and mismatched end tags or
+    /// elements left open fail with [`XmlError::UnbalancedTags`].
+    ///
+    /// This only checks that the document is well-formed. Which namespaces a
+    /// field accepts is set by `namespace_matching`.
+    pub strict_namespaces: bool,
+    /// Whether attributes that no field accepts are an error, for every type (default: None)
+    ///
+    /// `None` leaves it to each type: `deny_unknown_fields` or
+    /// `xml::deny_unknown_attributes` make them an error. `Some(true)` rejects and
+    /// `Some(false)` ignores them everywhere, whatever the types say.
+    pub deny_unknown_attributes: Option<bool>,
+    /// Whether child elements that no field accepts are an error, for every type (default: None)
+    ///
+    /// Like `deny_unknown_attributes`, with `xml::deny_unknown_elements`.
+    pub deny_unknown_elements: Option<bool>,
+    /// Whether text that the target type cannot hold is dropped (default: false)
+    ///
+    /// By default, text among the children of a type whose content is an enum
+    /// without an `xml::text` variant is an error. When `true`, it is dropped,
+    /// as HTML parsers do.
+    pub lenient: bool,
+    /// How element and attribute names are matched against fields
+    /// (default: [`NameMatching::Exact`])
+    ///
+    /// Applies to the root element, child elements, attributes and enum variants.
+    /// An exact match is still preferred.
+    pub name_matching: NameMatching,
+    /// Name the root element must have, instead of the one derived from the
+    /// target type (default: None)
+    pub root_name: Option<Cow<'static, str>>,
+    /// How the namespaces of elements and attributes are matched against fields
+    /// (default: [`NamespaceMatching::IfDeclared`])
+    ///
+    /// Unbound prefixes resolve to no namespace before matching, unless
+    /// `strict_namespaces` rejects them first.
+    pub namespace_matching: NamespaceMatching,
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        Self {
+            preserve_whitespace: false,
+            limits: ParserLimits::new(),
+            deny_unknown_entities: false,
+            max_entity_depth: 16,
+            max_entity_expansion: 1 << 20,
+            strict_namespaces: false,
+            deny_unknown_attributes: None,
+            deny_unknown_elements: None,
+            lenient: false,
+            name_matching: NameMatching::Exact,
+            root_name: None,
+            namespace_matching: NamespaceMatching::IfDeclared,
+        }
+    }
+}
+
+impl DeserializeOptions {
+    /// Create new default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep whitespace in text content exactly as written.
+    pub const fn preserve_whitespace(mut self, preserve: bool) -> Self {
+        self.preserve_whitespace = preserve;
+        self
+    }
+
+    /// Set resource limits for the parser.
+    pub const fn limits(mut self, limits: ParserLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Make references to undeclared entities an error.
+    pub const fn deny_unknown_entities(mut self, deny: bool) -> Self {
+        self.deny_unknown_entities = deny;
+        self
+    }
+
+    /// Set the maximum nesting of references inside declared entities.
+    pub const fn max_entity_depth(mut self, max: usize) -> Self {
+        self.max_entity_depth = max;
+        self
+    }
+
+    /// Set the maximum total size produced by expanding declared entities.
+    pub const fn max_entity_expansion(mut self, max: usize) -> Self {
+        self.max_entity_expansion = max;
+        self
+    }
+
+    /// Reject undeclared namespace prefixes and unbalanced tags.
+    pub const fn strict_namespaces(mut self, strict: bool) -> Self {
+        self.strict_namespaces = strict;
+        self
+    }
+
+    /// Reject (`true`) or ignore (`false`) unknown attributes for every type,
+    /// overriding their `deny_unknown_fields` and `xml::deny_unknown_attributes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use facet_xml as xml;
+    /// use facet::Facet;
+    /// use facet_xml::DeserializeOptions;
+    ///
+    /// #[derive(Facet, Debug)]
+    /// #[facet(deny_unknown_fields)]
+    /// struct Link {
+    ///     #[facet(xml::attribute)]
+    ///     href: String,
+    /// }
+    ///
+    /// let input = r#"<link href="/" data-track="nav"/>"#;
+    /// assert!(xml::from_str::<Link>(input).is_err());
+    ///
+    /// let options = DeserializeOptions::new().deny_unknown_attributes(false);
+    /// let link: Link = xml::from_str_with_options(input, &options).unwrap();
+    /// assert_eq!(link.href, "/");
+    /// ```
+    pub const fn deny_unknown_attributes(mut self, deny: bool) -> Self {
+        self.deny_unknown_attributes = Some(deny);
+        self
+    }
+
+    /// Reject (`true`) or ignore (`false`) unknown child elements for every type,
+    /// overriding their `deny_unknown_fields` and `xml::deny_unknown_elements`.
+    pub const fn deny_unknown_elements(mut self, deny: bool) -> Self {
+        self.deny_unknown_elements = Some(deny);
+        self
+    }
+
+    /// Drop text that the target type cannot hold instead of failing.
+    pub const fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Set how element and attribute names are matched against fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_xml::{DeserializeOptions, NameMatching};
+    ///
+    /// #[derive(Facet, Debug)]
+    /// struct Book {
+    ///     title: String,
+    /// }
+    ///
+    /// let input = "<Book><Title>Dune</Title></Book>";
+    /// let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    /// let book: Book = facet_xml::from_str_with_options(input, &options).unwrap();
+    /// assert_eq!(book.title, "Dune");
+    /// ```
+    pub const fn name_matching(mut self, matching: NameMatching) -> Self {
+        self.name_matching = matching;
+        self
+    }
+
+    /// Require the root element to be named `name`.
+    pub fn root_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.root_name = Some(name.into());
+        self
+    }
+
+    /// Set how namespaces are matched against fields.
+    pub const fn namespace_matching(mut self, matching: NamespaceMatching) -> Self {
+        self.namespace_matching = matching;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -83,15 +512,148 @@ enum ParserState {
 
 impl<'de> XmlParser<'de> {
     /// Create a new streaming XML parser.
+    ///
+    /// The encoding is detected from a byte order mark or the XML declaration,
+    /// and non-UTF-8 input is transcoded. An unsupported or malformed encoding
+    /// is reported by the first call to [`DomParser::next_event`].
     pub fn new(input: &'de [u8]) -> Self {
         trace!(input_len = input.len(), "creating XML parser");
 
-        let mut reader = NsReader::from_reader(Cursor::new(input));
-        reader.config_mut().trim_text(true);
+        let input_len = input.len();
+        let (input, encoding, decode_error) = match encoding::decode(input) {
+            Ok((decoded, encoding)) => (decoded, encoding, None),
+            Err(e) => (SliceInput::Borrowed(input), Encoding::Utf8, Some(e)),
+        };
+        let reader = match input {
+            SliceInput::Borrowed(bytes) => Source::Slice(NsReader::from_reader(bytes)),
+            SliceInput::Transcoded(_) => {
+                Source::Buffered(NsReader::from_reader(Cursor::new(input.clone())))
+            }
+        };
+        // UTF-8 input is borrowed without its byte order mark
+        let bom_len = match input {
+            SliceInput::Borrowed(bytes) => input_len - bytes.len(),
+            SliceInput::Transcoded(_) => 0,
+        };
+        let mut parser = Self::with_input(reader, Some(input));
+        parser.bom_len = bom_len;
+        parser.encoding = encoding;
+        parser.decode_error = decode_error;
+        parser
+    }
+}
+
+impl<'de, R: Read> XmlParser<'de, BufReader<R>> {
+    /// Create a streaming XML parser reading from an I/O source.
+    ///
+    /// The source is wrapped in a [`BufReader`]; only owned deserialization is
+    /// possible since nothing can be borrowed from the input.
+    pub fn from_reader(reader: R) -> Self {
+        trace!("creating XML parser over reader");
+        Self::from_buf_reader(BufReader::new(reader))
+    }
+}
+
+impl<'de, R: BufRead> XmlParser<'de, R> {
+    /// Create a streaming XML parser reading from a buffered I/O source.
+    pub fn from_buf_reader(reader: R) -> Self {
+        Self::with_input(Source::Buffered(NsReader::from_reader(reader)), None)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'de, R: tokio::io::AsyncRead + Unpin> XmlParser<'de, tokio::io::BufReader<R>> {
+    /// Create an XML parser reading from an asynchronous I/O source.
+    ///
+    /// The source is wrapped in a tokio [`BufReader`](tokio::io::BufReader).
+    /// Events are read with [`XmlParser::next_event_async`].
+    pub fn from_async_reader(reader: R) -> Self {
+        trace!("creating XML parser over async reader");
+        Self::from_async_buf_reader(tokio::io::BufReader::new(reader))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'de, R: tokio::io::AsyncBufRead + Unpin> XmlParser<'de, R> {
+    /// Create an XML parser reading from an asynchronous buffered I/O source.
+    pub fn from_async_buf_reader(reader: R) -> Self {
+        Self::with_input(Source::Buffered(NsReader::from_reader(reader)), None)
+    }
+
+    /// Read the next event, waiting for input without blocking the executor.
+    ///
+    /// This is the asynchronous counterpart of [`DomParser::next_event`]; it
+    /// applies the same options and limits.
+    pub async fn next_event_async(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+        let event = self.read_next_async().await?;
+        self.count_event(event)
+    }
+
+    /// Read raw events from quick-xml until one converts to a DomEvent.
+    async fn read_next_async(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+        loop {
+            if let Some(event) = self.advance()? {
+                return Ok(Some(event));
+            }
+            if self.state == ParserState::Done {
+                return Ok(None);
+            }
+
+            let pos_before = self.reader.buffer_position();
+            let strict = self.options.strict_namespaces;
+
+            let Source::Buffered(reader) = &mut self.reader else {
+                unreachable!("async parsers read from a buffered source");
+            };
+            let mut buf = core::mem::take(&mut self.buf);
+            buf.clear();
+            let read = reader
+                .read_resolved_event_into_async(&mut buf)
+                .await
+                .map(|(resolve, event)| (resolve_namespace(resolve, strict), event));
+            let produced = self.handle_read(read, pos_before);
+            self.buf = buf;
+
+            if let Some(event) = produced? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Source span of the most recently read event.
+    ///
+    /// The asynchronous counterpart of [`DomParser::current_span`].
+    pub fn current_span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// The underlying reader.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        match &mut self.reader {
+            Source::Buffered(reader) => reader.get_mut(),
+            Source::Slice(_) => unreachable!("async parsers read from a buffered source"),
+        }
+    }
+}
+
+impl<'de, R> XmlParser<'de, R> {
+    fn with_input(mut reader: Source<'de, R>, input: Option<SliceInput<'de>>) -> Self {
+        // Trimming happens in `take_text`, after text and entity references are merged
+        reader.config_mut().trim_text(false);
+        let borrowable = match (&reader, &input) {
+            (Source::Slice(_), Some(SliceInput::Borrowed(bytes))) => {
+                core::str::from_utf8(bytes).ok()
+            }
+            _ => None,
+        };
 
         Self {
             reader,
             input,
+            borrowable,
+            encoding: Encoding::Utf8,
+            bom_len: 0,
+            decode_error: None,
             buf: Vec::new(),
             peeked: None,
             depth: 0,
@@ -100,51 +662,223 @@ impl<'de> XmlParser<'de> {
             state: ParserState::Ready,
             is_empty_element: false,
             node_start_pos: 0,
+            span: None,
+            options: DeserializeOptions::default(),
+            entities: Entities::default(),
+            space_stack: Vec::new(),
+            text_buf: String::new(),
+            text_borrowed: None,
+            text_span: None,
+            text_preserve: false,
+            queued: None,
+            event_count: 0,
+            element_count: 0,
+            open_tags: Vec::new(),
         }
     }
 
-    /// Capture the current node as raw XML and skip past it.
-    /// Must be called right after a NodeStart event has been consumed.
-    fn do_capture_raw_node(&mut self) -> Result<Cow<'de, str>, XmlError> {
-        // Save start position before it gets overwritten by child elements
-        let start = self.node_start_pos as usize;
-        let start_depth = self.depth;
+    /// The encoding of the original input.
+    ///
+    /// Parsers reading from an I/O source always report [`Encoding::Utf8`].
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
 
-        // Skip through the node - consume events until depth drops below starting
-        loop {
-            // Handle peeked event first
-            let event = if let Some(e) = self.peeked.take() {
-                Some(e)
-            } else {
-                self.read_next()?
-            };
+    /// Apply deserialization options to this parser.
+    pub fn with_options(mut self, options: &DeserializeOptions) -> Self {
+        self.options = options.clone();
+        self
+    }
 
-            match event {
-                Some(DomEvent::NodeEnd) if self.depth < start_depth => break,
-                None => break,
-                _ => {}
+    /// Set resource limits for this parser.
+    pub fn limits(mut self, limits: ParserLimits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    /// Keep whitespace in text content exactly as written.
+    ///
+    /// See [`DeserializeOptions::preserve_whitespace`].
+    pub fn preserve_whitespace(mut self, preserve: bool) -> Self {
+        self.options.preserve_whitespace = preserve;
+        self
+    }
+
+    /// Reject undeclared namespace prefixes and unbalanced tags.
+    ///
+    /// See [`DeserializeOptions::strict_namespaces`].
+    pub fn strict_namespaces(mut self, strict: bool) -> Self {
+        self.options.strict_namespaces = strict;
+        self
+    }
+
+    /// Reject or ignore unknown attributes for every type.
+    ///
+    /// See [`DeserializeOptions::deny_unknown_attributes`].
+    pub fn deny_unknown_attributes(mut self, deny: bool) -> Self {
+        self.options.deny_unknown_attributes = Some(deny);
+        self
+    }
+
+    /// Reject or ignore unknown child elements for every type.
+    ///
+    /// See [`DeserializeOptions::deny_unknown_elements`].
+    pub fn deny_unknown_elements(mut self, deny: bool) -> Self {
+        self.options.deny_unknown_elements = Some(deny);
+        self
+    }
+
+    /// Drop text that the target type cannot hold instead of failing.
+    ///
+    /// See [`DeserializeOptions::lenient`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.options.lenient = lenient;
+        self
+    }
+
+    /// Set how element and attribute names are matched against fields.
+    ///
+    /// See [`DeserializeOptions::name_matching`].
+    pub fn name_matching(mut self, matching: NameMatching) -> Self {
+        self.options.name_matching = matching;
+        self
+    }
+
+    /// Require the root element to be named `name`.
+    ///
+    /// See [`DeserializeOptions::root_name`].
+    pub fn root_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.options.root_name = Some(name.into());
+        self
+    }
+
+    /// Set how namespaces are matched against fields.
+    ///
+    /// See [`DeserializeOptions::namespace_matching`].
+    pub fn namespace_matching(mut self, matching: NamespaceMatching) -> Self {
+        self.options.namespace_matching = matching;
+        self
+    }
+
+    /// Append a piece of text to the pending Text event.
+    ///
+    /// A borrowed first piece is kept as-is; it is only copied into `text_buf`
+    /// if more pieces follow.
+    fn push_text(&mut self, text: Cow<'de, str>, span: Span) -> Result<(), XmlError> {
+        if self.text_span.is_none() {
+            // Text outside the root element is never significant
+            self.text_preserve =
+                self.depth > 0 && self.space_stack.last().copied().unwrap_or(false);
+            match text {
+                Cow::Borrowed(text) => self.text_borrowed = Some(text),
+                Cow::Owned(text) => self.text_buf = text,
             }
+        } else {
+            if let Some(first) = self.text_borrowed.take() {
+                self.text_buf.push_str(first);
+            }
+            self.text_buf.push_str(&text);
         }
+        self.text_span = Some(match self.text_span {
+            Some(start) => Span::new(start.offset as usize, span.end() - start.offset as usize),
+            None => span,
+        });
+        ParserLimits::check(
+            self.options.limits.max_text_size,
+            LimitKind::TextSize,
+            self.text_borrowed.map_or(self.text_buf.len(), str::len),
+        )
+    }
 
-        let end = self.reader.buffer_position() as usize;
-        let raw = &self.input[start..end];
-        let s = core::str::from_utf8(raw).map_err(XmlError::InvalidUtf8)?;
-        Ok(Cow::Borrowed(s))
+    /// Take the pending text, trimmed unless whitespace is preserved.
+    ///
+    /// Returns `None` if there is no text, or only whitespace that is not preserved.
+    fn take_text(&mut self) -> Option<(Cow<'de, str>, Option<Span>)> {
+        let span = self.text_span.take()?;
+        let text = match self.text_borrowed.take() {
+            Some(text) if self.text_preserve => Cow::Borrowed(text),
+            Some(text) => Cow::Borrowed(text.trim()),
+            None => {
+                let mut text = core::mem::take(&mut self.text_buf);
+                if !self.text_preserve {
+                    let trimmed = text.trim();
+                    if trimmed.len() != text.len() {
+                        text = trimmed.to_string();
+                    }
+                }
+                Cow::Owned(text)
+            }
+        };
+        (!text.is_empty()).then_some((text, Some(span)))
     }
 
-    /// Read the next raw event from quick-xml and convert to DomEvent.
-    fn read_next(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+    /// Convert an error from quick-xml, reporting tag mismatches as such in strict mode.
+    fn read_error(&self, error: quick_xml::Error) -> XmlError {
+        match error {
+            quick_xml::Error::IllFormed(IllFormedError::MismatchedEndTag { expected, found })
+                if self.options.strict_namespaces =>
+            {
+                XmlError::UnbalancedTags {
+                    expected: Some(expected),
+                    found: Some(found),
+                }
+            }
+            quick_xml::Error::IllFormed(IllFormedError::UnmatchedEndTag(found))
+                if self.options.strict_namespaces =>
+            {
+                XmlError::UnbalancedTags {
+                    expected: None,
+                    found: Some(found),
+                }
+            }
+            error => XmlError::Parse(error.to_string()),
+        }
+    }
+
+    /// Count an emitted event against the event limit.
+    fn count_event(
+        &mut self,
+        event: Option<DomEvent<'de>>,
+    ) -> Result<Option<DomEvent<'de>>, XmlError> {
+        if event.is_some() {
+            self.event_count += 1;
+            ParserLimits::check(
+                self.options.limits.max_events,
+                LimitKind::Events,
+                self.event_count,
+            )?;
+        }
+        Ok(event)
+    }
+
+    /// Emit the next event that needs no input: a queued event, a decoding
+    /// error, or an event synthesized around an element.
+    ///
+    /// Returns `None` at the end of the document, or when the next XML event
+    /// has to be read.
+    fn advance(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+        if let Some((event, span)) = self.queued.take() {
+            self.span = span;
+            return Ok(Some(event));
+        }
+        if let Some(e) = self.decode_error.take() {
+            self.state = ParserState::Done;
+            return Err(e);
+        }
+
         loop {
             match self.state {
-                ParserState::Done => return Ok(None),
+                ParserState::Done | ParserState::Ready | ParserState::InChildren => {
+                    return Ok(None);
+                }
 
                 ParserState::EmittingAttrs => {
                     if self.attr_idx < self.pending_attrs.len() {
-                        let (ns, name, value) = &self.pending_attrs[self.attr_idx];
+                        let (ns, name, value) = &mut self.pending_attrs[self.attr_idx];
                         let event = DomEvent::Attribute {
-                            name: Cow::Owned(name.clone()),
-                            value: Cow::Owned(value.clone()),
-                            namespace: ns.clone().map(Cow::Owned),
+                            name: core::mem::take(name),
+                            value: core::mem::take(value),
+                            namespace: ns.take().map(Cow::Owned),
                         };
                         self.attr_idx += 1;
                         return Ok(Some(event));
@@ -172,6 +906,7 @@ impl<'de> XmlParser<'de> {
 
                 ParserState::NeedNodeEnd => {
                     self.depth -= 1;
+                    self.space_stack.pop();
                     self.state = if self.depth == 0 {
                         ParserState::Done
                     } else {
@@ -179,152 +914,326 @@ impl<'de> XmlParser<'de> {
                     };
                     return Ok(Some(DomEvent::NodeEnd));
                 }
+            }
+        }
+    }
+
+    /// Convert a raw event from quick-xml into a DomEvent.
+    ///
+    /// `read` carries the event with its already resolved element namespace,
+    /// and `pos_before` is the input position before it was read. Returns
+    /// `None` if nothing is ready to emit yet, e.g. after a piece of text.
+    fn handle_read(
+        &mut self,
+        read: Result<(Result<Option<String>, XmlError>, Event<'_>), quick_xml::Error>,
+        pos_before: u64,
+    ) -> Result<Option<DomEvent<'de>>, XmlError> {
+        let (elem_ns, event) = match read {
+            Ok(result) => result,
+            Err(e) => {
+                let pos = self.reader.error_position() as usize;
+                self.span = Some(Span::new(pos, 0));
+                return Err(self.read_error(e));
+            }
+        };
+
+        // Namespace errors are reported at this event
+        let strict = self.options.strict_namespaces;
+        self.span = Some(event_span(
+            self.input.as_ref().map(AsRef::as_ref),
+            pos_before,
+            self.reader.buffer_position(),
+        ));
+        let elem_ns = elem_ns?;
+
+        let produced = match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                // Record start position for potential raw capture
+                self.node_start_pos = pos_before;
+
+                let input = self.borrowable;
 
-                ParserState::Ready | ParserState::InChildren => {
-                    // Record position before reading (for raw capture)
-                    let pos_before = self.reader.buffer_position();
-
-                    self.buf.clear();
-                    let (resolve, event) = self
-                        .reader
-                        .read_resolved_event_into(&mut self.buf)
-                        .map_err(|e| XmlError::Parse(e.to_string()))?;
-
-                    // Resolve element namespace upfront
-                    let elem_ns = resolve_namespace(resolve)?;
-
-                    match event {
-                        Event::Start(ref e) | Event::Empty(ref e) => {
-                            let is_empty = matches!(event, Event::Empty(_));
-                            // Record start position for potential raw capture
-                            self.node_start_pos = pos_before;
-
-                            // Get element local name
-                            let local_name = e.local_name();
-                            let local = core::str::from_utf8(local_name.as_ref())
-                                .map_err(XmlError::InvalidUtf8)?;
-                            let local_owned = local.to_string();
-
-                            // Collect attributes
-                            self.pending_attrs.clear();
-                            self.attr_idx = 0;
-
-                            for attr in e.attributes() {
-                                let attr = attr.map_err(|e| XmlError::Parse(e.to_string()))?;
-
-                                // Skip xmlns declarations
-                                let key = attr.key;
-                                if key.as_ref() == b"xmlns" {
-                                    continue;
-                                }
-                                if let Some(prefix) = key.prefix()
-                                    && prefix.as_ref() == b"xmlns"
-                                {
-                                    continue;
-                                }
-
-                                let (attr_resolve, _) =
-                                    self.reader.resolver().resolve_attribute(key);
-                                let attr_ns = resolve_namespace(attr_resolve)?;
-                                let attr_local_name = key.local_name();
-                                let attr_local = core::str::from_utf8(attr_local_name.as_ref())
-                                    .map_err(XmlError::InvalidUtf8)?;
-                                let value = attr
-                                    .unescape_value()
-                                    .map_err(|e| XmlError::Parse(e.to_string()))?;
-
-                                self.pending_attrs.push((
-                                    attr_ns,
-                                    attr_local.to_string(),
-                                    value.into_owned(),
-                                ));
-                            }
-
-                            self.depth += 1;
-                            self.is_empty_element = is_empty;
-
-                            if self.pending_attrs.is_empty() {
-                                self.state = ParserState::NeedChildrenStart;
-                            } else {
-                                self.state = ParserState::EmittingAttrs;
-                            }
-
-                            return Ok(Some(DomEvent::NodeStart {
-                                tag: Cow::Owned(local_owned),
-                                namespace: elem_ns.map(Cow::Owned),
-                            }));
-                        }
-                        Event::End(_) => {
-                            self.state = ParserState::NeedChildrenEnd;
-                        }
-                        Event::Text(e) => {
-                            let text = e.decode().map_err(|e| XmlError::Parse(e.to_string()))?;
-                            let trimmed = text.trim();
-                            if !trimmed.is_empty() {
-                                return Ok(Some(DomEvent::Text(Cow::Owned(trimmed.to_string()))));
-                            }
-                        }
-                        Event::CData(e) => {
-                            let text =
-                                core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
-                            if !text.is_empty() {
-                                return Ok(Some(DomEvent::Text(Cow::Owned(text.to_string()))));
-                            }
-                        }
-                        Event::Comment(e) => {
-                            let text =
-                                core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
-                            return Ok(Some(DomEvent::Comment(Cow::Owned(text.to_string()))));
-                        }
-                        Event::PI(e) => {
-                            let content =
-                                core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
-                            let (target, data) = content
-                                .split_once(char::is_whitespace)
-                                .unwrap_or((content, ""));
-                            return Ok(Some(DomEvent::ProcessingInstruction {
-                                target: Cow::Owned(target.to_string()),
-                                data: Cow::Owned(data.trim().to_string()),
-                            }));
-                        }
-                        Event::Decl(_) => {
-                            // XML declaration - skip
-                        }
-                        Event::DocType(e) => {
-                            // Parse DOCTYPE declaration and emit as DomEvent
-                            let text =
-                                core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
-                            return Ok(Some(DomEvent::Doctype(Cow::Owned(text.to_string()))));
-                        }
-                        Event::Eof => {
-                            self.state = ParserState::Done;
-                            return Ok(None);
-                        }
-                        Event::GeneralRef(e) => {
-                            let raw = e.decode().map_err(|e| XmlError::Parse(e.to_string()))?;
-                            let resolved = resolve_entity(&raw)?;
-                            return Ok(Some(DomEvent::Text(Cow::Owned(resolved))));
-                        }
+                // Get element local name
+                let local_name = e.local_name();
+                let local =
+                    core::str::from_utf8(local_name.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                let tag = borrow_input(input, local)
+                    .map_or_else(|| Cow::Owned(local.to_string()), Cow::Borrowed);
+
+                // Collect attributes
+                self.pending_attrs.clear();
+                self.attr_idx = 0;
+                let mut xml_space = None;
+
+                self.element_count += 1;
+                ParserLimits::check(
+                    self.options.limits.max_elements,
+                    LimitKind::Elements,
+                    self.element_count,
+                )?;
+                ParserLimits::check(
+                    self.options.limits.max_depth,
+                    LimitKind::Depth,
+                    self.depth + 1,
+                )?;
+
+                for (count, attr) in e.attributes().enumerate() {
+                    ParserLimits::check(
+                        self.options.limits.max_attributes,
+                        LimitKind::Attributes,
+                        count + 1,
+                    )?;
+                    let attr = attr.map_err(|e| XmlError::Parse(e.to_string()))?;
+
+                    // Skip xmlns declarations
+                    let key = attr.key;
+                    if key.as_ref() == b"xmlns" {
+                        continue;
+                    }
+                    if let Some(prefix) = key.prefix()
+                        && prefix.as_ref() == b"xmlns"
+                    {
+                        continue;
                     }
+
+                    let (attr_resolve, _) = self.reader.resolver().resolve_attribute(key);
+                    let attr_ns = resolve_namespace(attr_resolve, strict)?;
+                    let attr_local_name = key.local_name();
+                    let attr_local = core::str::from_utf8(attr_local_name.as_ref())
+                        .map_err(XmlError::InvalidUtf8)?;
+                    let raw_value =
+                        core::str::from_utf8(attr.value.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                    let borrowed_value = if raw_value.contains('&') {
+                        None
+                    } else {
+                        borrow_input(input, raw_value)
+                    };
+                    let value = match borrowed_value {
+                        Some(value) => Cow::Borrowed(value),
+                        None => Cow::Owned(self.entities.unescape(raw_value, &self.options)?),
+                    };
+                    let name = borrow_input(input, attr_local)
+                        .map_or_else(|| Cow::Owned(attr_local.to_string()), Cow::Borrowed);
+
+                    if key.as_ref() == b"xml:space" {
+                        xml_space = Some(value == "preserve");
+                    }
+
+                    self.pending_attrs.push((attr_ns, name, value));
+                }
+
+                if strict && !is_empty {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                    self.open_tags.push(name);
+                }
+                self.depth += 1;
+                self.is_empty_element = is_empty;
+                // `xml:space="default"` reverts to the parser's own setting
+                let inherited = self
+                    .space_stack
+                    .last()
+                    .copied()
+                    .unwrap_or(self.options.preserve_whitespace);
+                self.space_stack.push(match xml_space {
+                    Some(true) => true,
+                    Some(false) => self.options.preserve_whitespace,
+                    None => inherited,
+                });
+
+                if self.pending_attrs.is_empty() {
+                    self.state = ParserState::NeedChildrenStart;
+                } else {
+                    self.state = ParserState::EmittingAttrs;
+                }
+
+                Some(DomEvent::NodeStart {
+                    tag,
+                    namespace: elem_ns.map(Cow::Owned),
+                })
+            }
+            Event::End(_) => {
+                self.open_tags.pop();
+                self.state = ParserState::NeedChildrenEnd;
+                None
+            }
+            Event::Text(e) => {
+                let decoded = e.decode().map_err(|e| XmlError::Parse(e.to_string()))?;
+                let text = borrow_input(self.borrowable, &decoded)
+                    .map_or_else(|| Cow::Owned(decoded.into_owned()), Cow::Borrowed);
+                let span = self.span.unwrap_or_default();
+                self.push_text(text, span)?;
+                return Ok(None);
+            }
+            Event::GeneralRef(e) => {
+                let raw = e.decode().map_err(|e| XmlError::Parse(e.to_string()))?;
+                let mut resolved = String::new();
+                self.entities.resolve(&raw, &mut resolved, &self.options)?;
+                let span = self.span.unwrap_or_default();
+                self.push_text(Cow::Owned(resolved), span)?;
+                return Ok(None);
+            }
+            Event::CData(e) => {
+                let text = core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                ParserLimits::check(
+                    self.options.limits.max_text_size,
+                    LimitKind::TextSize,
+                    text.len(),
+                )?;
+                let text = borrow_input(self.borrowable, text)
+                    .map_or_else(|| Cow::Owned(text.to_string()), Cow::Borrowed);
+                (!text.is_empty()).then_some(DomEvent::CData(text))
+            }
+            Event::Comment(e) => {
+                let text = core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                Some(DomEvent::Comment(Cow::Owned(text.to_string())))
+            }
+            Event::PI(e) => {
+                let content = core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                let (target, data) = content
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((content, ""));
+                Some(DomEvent::ProcessingInstruction {
+                    target: Cow::Owned(target.to_string()),
+                    data: Cow::Owned(data.trim().to_string()),
+                })
+            }
+            Event::Decl(e) => {
+                let version = e.version().map_err(|e| XmlError::Parse(e.to_string()))?;
+                let encoding = e
+                    .encoding()
+                    .transpose()
+                    .map_err(|e| XmlError::Parse(e.to_string()))?;
+                let standalone = e
+                    .standalone()
+                    .transpose()
+                    .map_err(|e| XmlError::Parse(e.to_string()))?;
+                Some(DomEvent::XmlDeclaration {
+                    version: Cow::Owned(decl_value(&version)?),
+                    encoding: encoding
+                        .map(|encoding| decl_value(&encoding))
+                        .transpose()?
+                        .map(Cow::Owned),
+                    standalone: standalone.map(|standalone| *standalone == *b"yes"),
+                })
+            }
+            Event::DocType(e) => {
+                // Parse DOCTYPE declaration and emit as DomEvent
+                let text = core::str::from_utf8(e.as_ref()).map_err(XmlError::InvalidUtf8)?;
+                self.entities.declare_from_doctype(text);
+                Some(DomEvent::Doctype(Cow::Owned(text.to_string())))
+            }
+            Event::Eof => {
+                self.state = ParserState::Done;
+                if strict && self.depth > 0 {
+                    return Err(XmlError::UnbalancedTags {
+                        expected: self.open_tags.pop(),
+                        found: None,
+                    });
                 }
+                None
+            }
+        };
+
+        // Any other event ends a run of text, which is emitted first
+        if let Some((text, text_span)) = self.take_text() {
+            if let Some(event) = produced {
+                self.queued = Some((event, self.span));
+            }
+            self.span = text_span;
+            return Ok(Some(DomEvent::Text(text)));
+        }
+        Ok(produced)
+    }
+}
+
+impl<'de, R: BufRead> XmlParser<'de, R> {
+    /// Capture the current node as raw XML and skip past it.
+    /// Must be called right after a NodeStart event has been consumed.
+    fn do_capture_raw_node(&mut self, input: SliceInput<'de>) -> Result<Cow<'de, str>, XmlError> {
+        // Save start position before it gets overwritten by child elements
+        let start = self.node_start_pos as usize;
+        let start_depth = self.depth;
+
+        // Skip through the node - consume events until depth drops below starting
+        loop {
+            // Handle peeked event first
+            let event = if let Some(e) = self.peeked.take() {
+                Some(e)
+            } else {
+                self.read_event()?
+            };
+
+            match event {
+                Some(DomEvent::NodeEnd) if self.depth < start_depth => break,
+                None => break,
+                _ => {}
+            }
+        }
+
+        let end = self.reader.buffer_position() as usize;
+        match input {
+            SliceInput::Borrowed(input) => core::str::from_utf8(&input[start..end])
+                .map(Cow::Borrowed)
+                .map_err(XmlError::InvalidUtf8),
+            SliceInput::Transcoded(input) => core::str::from_utf8(&input[start..end])
+                .map(|s| Cow::Owned(s.to_string()))
+                .map_err(XmlError::InvalidUtf8),
+        }
+    }
+
+    /// Read the next event, enforcing the event count limit.
+    fn read_event(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+        let event = self.read_next()?;
+        self.count_event(event)
+    }
+
+    /// Read raw events from quick-xml until one converts to a DomEvent.
+    fn read_next(&mut self) -> Result<Option<DomEvent<'de>>, XmlError> {
+        loop {
+            if let Some(event) = self.advance()? {
+                return Ok(Some(event));
+            }
+            if self.state == ParserState::Done {
+                return Ok(None);
+            }
+
+            // Record position before reading (for raw capture)
+            let pos_before = self.reader.buffer_position();
+            let strict = self.options.strict_namespaces;
+
+            // Resolve the namespace right away so the event only borrows the
+            // input or the buffer
+            let mut buf = core::mem::take(&mut self.buf);
+            buf.clear();
+            let read = match &mut self.reader {
+                Source::Slice(reader) => reader.read_resolved_event(),
+                Source::Buffered(reader) => reader.read_resolved_event_into(&mut buf),
+            }
+            .map(|(resolve, event)| (resolve_namespace(resolve, strict), event));
+            let produced = self.handle_read(read, pos_before);
+            self.buf = buf;
+
+            if let Some(event) = produced? {
+                return Ok(Some(event));
             }
         }
     }
 }
 
-impl<'de> DomParser<'de> for XmlParser<'de> {
+impl<'de, R: BufRead> DomParser<'de> for XmlParser<'de, R> {
     type Error = XmlError;
 
     fn next_event(&mut self) -> Result<Option<DomEvent<'de>>, Self::Error> {
         if let Some(event) = self.peeked.take() {
             return Ok(Some(event));
         }
-        self.read_next()
+        self.read_event()
     }
 
     fn peek_event(&mut self) -> Result<Option<&DomEvent<'de>>, Self::Error> {
         if self.peeked.is_none() {
-            self.peeked = self.read_next()?;
+            self.peeked = self.read_event()?;
         }
         Ok(self.peeked.as_ref())
     }
@@ -348,8 +1257,41 @@ impl<'de> DomParser<'de> for XmlParser<'de> {
         Ok(())
     }
 
-    fn current_span(&self) -> Option<facet_reflect::Span> {
-        None
+    fn current_span(&self) -> Option<Span> {
+        self.span
+            .map(|span| Span::new(span.offset as usize + self.bom_len, span.len as usize))
+    }
+
+    fn location_at(&self, offset: usize) -> Option<SourceLocation> {
+        // The byte order mark is not a character of the first line
+        let offset = offset.saturating_sub(self.bom_len);
+        self.input
+            .as_ref()
+            .map(|input| SourceLocation::from_offset(input.as_ref(), offset))
+    }
+
+    fn deny_unknown_attributes(&self) -> Option<bool> {
+        self.options.deny_unknown_attributes
+    }
+
+    fn deny_unknown_elements(&self) -> Option<bool> {
+        self.options.deny_unknown_elements
+    }
+
+    fn is_lenient(&self) -> bool {
+        self.options.lenient
+    }
+
+    fn name_matching(&self) -> NameMatching {
+        self.options.name_matching
+    }
+
+    fn namespace_matching(&self) -> NamespaceMatching {
+        self.options.namespace_matching
+    }
+
+    fn root_name(&self) -> Option<&str> {
+        self.options.root_name.as_deref()
     }
 
     fn format_namespace(&self) -> Option<&'static str> {
@@ -357,38 +1299,97 @@ impl<'de> DomParser<'de> for XmlParser<'de> {
     }
 
     fn capture_raw_node(&mut self) -> Result<Option<Cow<'de, str>>, Self::Error> {
-        Ok(Some(self.do_capture_raw_node()?))
+        match self.input.clone() {
+            Some(input) => Ok(Some(self.do_capture_raw_node(input)?)),
+            None => Ok(None),
+        }
     }
 }
 
-/// Resolve a namespace from quick-xml's ResolveResult.
-fn resolve_namespace(resolve: ResolveResult<'_>) -> Result<Option<String>, XmlError> {
-    match resolve {
-        ResolveResult::Bound(ns) => Ok(Some(String::from_utf8_lossy(ns.as_ref()).into_owned())),
-        ResolveResult::Unbound => Ok(None),
-        ResolveResult::Unknown(_) => Ok(None),
-    }
+/// Compute the span of an event that occupies `input[start..end]`.
+///
+/// Whitespace skipped by text trimming is excluded so the span points at the
+/// first significant byte of the event. Without the input (when streaming),
+/// the span covers the whole range.
+fn event_span(input: Option<&[u8]>, start: u64, end: u64) -> Span {
+    let Some(input) = input else {
+        return Span::new(start as usize, (end - start) as usize);
+    };
+    let end = (end as usize).min(input.len());
+    let start = (start as usize).min(end);
+    let skipped = input[start..end]
+        .iter()
+        .take_while(|b| b.is_ascii_whitespace())
+        .count();
+    Span::new(start + skipped, end - start - skipped)
+}
+
+/// Borrow `part` of an event from the input it was read from.
+///
+/// Events read from borrowed input point into it, so this only locates `part`
+/// there. Returns `None` when there is no input to borrow from, or `part` lies
+/// elsewhere, e.g. in text that had to be unescaped.
+fn borrow_input<'de>(input: Option<&'de str>, part: &str) -> Option<&'de str> {
+    let input = input?;
+    let start = (part.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+    input.get(start..start.checked_add(part.len())?)
+}
+
+/// The quick-xml reader: over the input itself when it is borrowed, so events
+/// point into it, or over any buffered source, which copies each event into
+/// the parser's buffer.
+enum Source<'de, R> {
+    Slice(NsReader<&'de [u8]>),
+    Buffered(NsReader<R>),
 }
 
-/// Resolve a general entity reference.
-fn resolve_entity(raw: &str) -> Result<String, XmlError> {
-    if let Some(resolved) = resolve_xml_entity(raw) {
-        return Ok(resolved.into());
+impl<R> Source<'_, R> {
+    fn config_mut(&mut self) -> &mut Config {
+        match self {
+            Source::Slice(reader) => reader.config_mut(),
+            Source::Buffered(reader) => reader.config_mut(),
+        }
     }
 
-    if let Some(rest) = raw.strip_prefix('#') {
-        let code = if let Some(hex) = rest.strip_prefix('x').or_else(|| rest.strip_prefix('X')) {
-            u32::from_str_radix(hex, 16)
-                .map_err(|_| XmlError::Parse(format!("Invalid hex entity: #{}", rest)))?
-        } else {
-            rest.parse::<u32>()
-                .map_err(|_| XmlError::Parse(format!("Invalid decimal entity: #{}", rest)))?
-        };
+    fn resolver(&self) -> &NamespaceResolver {
+        match self {
+            Source::Slice(reader) => reader.resolver(),
+            Source::Buffered(reader) => reader.resolver(),
+        }
+    }
 
-        let ch = char::from_u32(code)
-            .ok_or_else(|| XmlError::Parse(format!("Invalid Unicode: {}", code)))?;
-        return Ok(ch.to_string());
+    fn buffer_position(&self) -> u64 {
+        match self {
+            Source::Slice(reader) => reader.buffer_position(),
+            Source::Buffered(reader) => reader.buffer_position(),
+        }
+    }
+
+    fn error_position(&self) -> u64 {
+        match self {
+            Source::Slice(reader) => reader.error_position(),
+            Source::Buffered(reader) => reader.error_position(),
+        }
     }
+}
+
+/// Decode a pseudo-attribute value from the XML declaration.
+fn decl_value(value: &[u8]) -> Result<String, XmlError> {
+    core::str::from_utf8(value)
+        .map(str::to_string)
+        .map_err(XmlError::InvalidUtf8)
+}
 
-    Ok(format!("&{};", raw))
+/// Resolve a namespace from quick-xml's ResolveResult.
+///
+/// An undeclared prefix is an error in strict mode, and no namespace otherwise.
+fn resolve_namespace(resolve: ResolveResult<'_>, strict: bool) -> Result<Option<String>, XmlError> {
+    match resolve {
+        ResolveResult::Bound(ns) => Ok(Some(String::from_utf8_lossy(ns.as_ref()).into_owned())),
+        ResolveResult::Unbound => Ok(None),
+        ResolveResult::Unknown(prefix) if strict => Err(XmlError::UnboundPrefix {
+            prefix: String::from_utf8_lossy(&prefix).into_owned(),
+        }),
+        ResolveResult::Unknown(_) => Ok(None),
+    }
 }
@@ -0,0 +1,177 @@
+//! Detection and transcoding of document encodings.
+
+use core::fmt;
+use std::sync::Arc;
+
+use crate::dom_parser::XmlError;
+
+/// The character encoding of an XML document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// ISO-8859-1 (Latin-1).
+    Latin1,
+    /// Windows-1252, the Western European superset of Latin-1.
+    Windows1252,
+}
+
+impl Encoding {
+    /// The canonical name of this encoding, as used in XML declarations.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Look up an encoding by the label used in an XML declaration.
+    ///
+    /// Labels are matched case-insensitively. `US-ASCII` is treated as UTF-8,
+    /// of which it is a subset. Plain `UTF-16` is not recognized, since it does
+    /// not say which byte order is used; that comes from the byte order mark.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim().to_ascii_lowercase();
+        Some(match label.as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" => Encoding::Utf8,
+            "utf-16le" => Encoding::Utf16Le,
+            "utf-16be" => Encoding::Utf16Be,
+            "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1" => {
+                Encoding::Latin1
+            }
+            "windows-1252" | "cp1252" | "x-cp1252" => Encoding::Windows1252,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Detect the encoding of an XML document from its byte order mark or its
+/// XML declaration, defaulting to UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use facet_xml::{Encoding, detect_encoding};
+///
+/// let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><name>Ren\xe9</name>";
+/// assert_eq!(detect_encoding(latin1).unwrap(), Encoding::Latin1);
+/// assert_eq!(detect_encoding(b"\xff\xfe<\0a\0/\0>\0").unwrap(), Encoding::Utf16Le);
+/// ```
+pub fn detect_encoding(input: &[u8]) -> Result<Encoding, XmlError> {
+    detect(input).map(|(encoding, _)| encoding)
+}
+
+/// Detect the encoding and the length of any byte order mark.
+fn detect(input: &[u8]) -> Result<(Encoding, usize), XmlError> {
+    match input {
+        [0xEF, 0xBB, 0xBF, ..] => return Ok((Encoding::Utf8, 3)),
+        [0xFF, 0xFE, ..] => return Ok((Encoding::Utf16Le, 2)),
+        [0xFE, 0xFF, ..] => return Ok((Encoding::Utf16Be, 2)),
+        // `<?` without a byte order mark
+        [b'<', 0, b'?', 0, ..] => return Ok((Encoding::Utf16Le, 0)),
+        [0, b'<', 0, b'?', ..] => return Ok((Encoding::Utf16Be, 0)),
+        _ => {}
+    }
+
+    // The declaration was readable as ASCII, so the input cannot be UTF-16
+    // whatever it claims
+    match declared_encoding(input) {
+        Some(label) => match Encoding::from_label(label) {
+            Some(Encoding::Utf16Le | Encoding::Utf16Be) | None => {
+                Err(XmlError::UnsupportedEncoding(label.to_string()))
+            }
+            Some(encoding) => Ok((encoding, 0)),
+        },
+        None => Ok((Encoding::Utf8, 0)),
+    }
+}
+
+/// Extract the `encoding` pseudo-attribute from an ASCII-compatible XML declaration.
+fn declared_encoding(input: &[u8]) -> Option<&str> {
+    let rest = input.strip_prefix(b"<?xml")?;
+    let end = rest.windows(2).position(|w| w == b"?>")?;
+    let decl = core::str::from_utf8(&rest[..end]).ok()?;
+    let (_, after) = decl.split_once("encoding")?;
+    let after = after.trim_start().strip_prefix('=')?.trim_start();
+    let quote = after.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &after[1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// In-memory input for [`XmlParser`](crate::XmlParser): the caller's bytes, or
+/// a UTF-8 transcoding of them.
+#[derive(Debug, Clone)]
+pub enum SliceInput<'de> {
+    /// UTF-8 input, borrowed from the caller.
+    Borrowed(&'de [u8]),
+    /// Input transcoded to UTF-8 from another encoding.
+    Transcoded(Arc<[u8]>),
+}
+
+impl AsRef<[u8]> for SliceInput<'_> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            SliceInput::Borrowed(bytes) => bytes,
+            SliceInput::Transcoded(bytes) => bytes,
+        }
+    }
+}
+
+/// Detect the encoding of `input` and convert it to UTF-8.
+///
+/// UTF-8 input is borrowed, without its byte order mark.
+pub(crate) fn decode(input: &[u8]) -> Result<(SliceInput<'_>, Encoding), XmlError> {
+    let (encoding, bom) = detect(input)?;
+    let body = &input[bom..];
+    let text: String = match encoding {
+        Encoding::Utf8 => return Ok((SliceInput::Borrowed(body), encoding)),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if !body.len().is_multiple_of(2) {
+                return Err(XmlError::Parse(format!(
+                    "odd number of bytes in {encoding} input"
+                )));
+            }
+            let units = body.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units)
+                .collect::<Result<_, _>>()
+                .map_err(|e| XmlError::Parse(format!("invalid {encoding} input: {e}")))?
+        }
+        Encoding::Latin1 => body.iter().map(|&b| char::from(b)).collect(),
+        Encoding::Windows1252 => body.iter().map(|&b| windows_1252_char(b)).collect(),
+    };
+    Ok((SliceInput::Transcoded(text.into_bytes().into()), encoding))
+}
+
+/// Decode a single Windows-1252 byte.
+fn windows_1252_char(byte: u8) -> char {
+    /// Code points for 0x80..=0x9F; the rest of the range matches Latin-1.
+    /// Unassigned bytes map to the C1 control with the same value.
+    const HIGH: [char; 32] = [
+        '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}',
+        '\u{017D}', '\u{008F}', '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+        '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}',
+        '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[usize::from(byte - 0x80)],
+        _ => char::from(byte),
+    }
+}
@@ -0,0 +1,242 @@
+//! Entity declarations from the DTD internal subset, and their expansion.
+
+use std::collections::HashMap;
+
+use quick_xml::escape::resolve_xml_entity;
+
+use crate::dom_parser::{DeserializeOptions, LimitKind, XmlError};
+
+/// General entities declared in the document, plus expansion bookkeeping.
+#[derive(Default)]
+pub(crate) struct Entities {
+    /// Replacement text of each declared entity, unexpanded
+    declared: HashMap<String, String>,
+    /// Total bytes produced so far by expanding declared entities
+    expanded: usize,
+}
+
+impl Entities {
+    /// Record the general entities declared in a DOCTYPE's internal subset.
+    ///
+    /// Parameter entities and external entities are ignored. If an entity is
+    /// declared more than once, the first declaration wins.
+    pub(crate) fn declare_from_doctype(&mut self, doctype: &str) {
+        for decl in markup_declarations(doctype) {
+            if let Some((name, value)) = parse_entity_declaration(decl) {
+                self.declared
+                    .entry(name.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+    }
+
+    /// Expand the reference `&name;` onto `out`.
+    pub(crate) fn resolve(
+        &mut self,
+        name: &str,
+        out: &mut String,
+        options: &DeserializeOptions,
+    ) -> Result<(), XmlError> {
+        self.expansion(options).resolve(name, out, 0)
+    }
+
+    /// Like [`Entities::resolve`], but fail if a declared entity's replacement
+    /// text contains markup rather than expanding it as character data.
+    pub(crate) fn resolve_text(
+        &mut self,
+        name: &str,
+        out: &mut String,
+        options: &DeserializeOptions,
+    ) -> Result<(), XmlError> {
+        let mut expansion = self.expansion(options);
+        expansion.reject_markup = true;
+        expansion.resolve(name, out, 0)
+    }
+
+    /// Replace every entity and character reference in `raw`, as found in an
+    /// attribute value.
+    pub(crate) fn unescape(
+        &mut self,
+        raw: &str,
+        options: &DeserializeOptions,
+    ) -> Result<String, XmlError> {
+        let mut out = String::with_capacity(raw.len());
+        self.expansion(options).expand_into(raw, &mut out, 0)?;
+        Ok(out)
+    }
+
+    fn expansion<'a>(&'a mut self, options: &'a DeserializeOptions) -> Expansion<'a> {
+        Expansion {
+            declared: &self.declared,
+            expanded: &mut self.expanded,
+            options,
+            reject_markup: false,
+        }
+    }
+}
+
+/// Expands references while charging the document's expansion budget.
+///
+/// Borrows the declarations separately from the budget, so replacement text is
+/// expanded in place rather than copied for every reference.
+struct Expansion<'a> {
+    declared: &'a HashMap<String, String>,
+    expanded: &'a mut usize,
+    options: &'a DeserializeOptions,
+    /// Whether replacement text containing `<` is an error
+    reject_markup: bool,
+}
+
+impl Expansion<'_> {
+    fn resolve(&mut self, name: &str, out: &mut String, depth: usize) -> Result<(), XmlError> {
+        if let Some(resolved) = resolve_xml_entity(name) {
+            return self.emit(resolved, out, depth);
+        }
+
+        if let Some(rest) = name.strip_prefix('#') {
+            let ch = resolve_char_ref(rest)?;
+            return self.emit(ch.encode_utf8(&mut [0; 4]), out, depth);
+        }
+
+        let declared = self.declared;
+        if let Some(value) = declared.get(name) {
+            if depth >= self.options.max_entity_depth {
+                return Err(XmlError::LimitExceeded {
+                    limit: LimitKind::EntityDepth,
+                    max: self.options.max_entity_depth,
+                });
+            }
+            if self.reject_markup && value.contains('<') {
+                return Err(XmlError::Parse(format!(
+                    "entity `{name}` expands to markup, which is not supported here"
+                )));
+            }
+            // Every reference costs at least its own `&name;`, so entities that
+            // expand to little or nothing cannot be referenced without bound
+            self.charge(name.len() + 2)?;
+            return self.expand_into(value, out, depth + 1);
+        }
+
+        if self.options.deny_unknown_entities {
+            return Err(XmlError::UnknownEntity(name.to_string()));
+        }
+        out.push('&');
+        out.push_str(name);
+        out.push(';');
+        Ok(())
+    }
+
+    /// Append `text` to `out`, replacing the references it contains.
+    fn expand_into(
+        &mut self,
+        mut text: &str,
+        out: &mut String,
+        depth: usize,
+    ) -> Result<(), XmlError> {
+        while let Some(amp) = text.find('&') {
+            self.emit(&text[..amp], out, depth)?;
+            let Some(semi) = text[amp..].find(';') else {
+                // A bare `&` is malformed; keep it as written
+                text = &text[amp..];
+                break;
+            };
+            self.resolve(&text[amp + 1..amp + semi], out, depth)?;
+            text = &text[amp + semi + 1..];
+        }
+        self.emit(text, out, depth)
+    }
+
+    /// Append literal text, counting it against the expansion limit when it
+    /// comes from a declared entity.
+    fn emit(&mut self, text: &str, out: &mut String, depth: usize) -> Result<(), XmlError> {
+        if depth > 0 {
+            self.charge(text.len())?;
+        }
+        out.push_str(text);
+        Ok(())
+    }
+
+    fn charge(&mut self, bytes: usize) -> Result<(), XmlError> {
+        *self.expanded += bytes;
+        if *self.expanded > self.options.max_entity_expansion {
+            return Err(XmlError::LimitExceeded {
+                limit: LimitKind::EntityExpansion,
+                max: self.options.max_entity_expansion,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Iterate over the markup declarations in a DOCTYPE's internal subset, each
+/// without its `<!` and `>`. Comments are skipped.
+pub(crate) fn markup_declarations(doctype: &str) -> impl Iterator<Item = &str> {
+    let mut rest = match doctype.find('[') {
+        Some(start) => {
+            let end = doctype
+                .rfind(']')
+                .filter(|&end| end > start)
+                .unwrap_or(doctype.len());
+            &doctype[start + 1..end]
+        }
+        None => "",
+    };
+
+    core::iter::from_fn(move || {
+        loop {
+            let pos = rest.find("<!")?;
+            rest = &rest[pos..];
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.split_once("-->").map_or("", |(_, after)| after);
+                continue;
+            }
+            let (decl, after) = split_declaration(&rest[2..]);
+            rest = after;
+            return Some(decl);
+        }
+    })
+}
+
+/// Resolve a character reference, given the part after `&#`.
+fn resolve_char_ref(rest: &str) -> Result<char, XmlError> {
+    let code = if let Some(hex) = rest.strip_prefix('x').or_else(|| rest.strip_prefix('X')) {
+        u32::from_str_radix(hex, 16)
+            .map_err(|_| XmlError::Parse(format!("Invalid hex entity: #{}", rest)))?
+    } else {
+        rest.parse::<u32>()
+            .map_err(|_| XmlError::Parse(format!("Invalid decimal entity: #{}", rest)))?
+    };
+
+    char::from_u32(code).ok_or_else(|| XmlError::Parse(format!("Invalid Unicode: {}", code)))
+}
+
+/// Split a markup declaration (after `<!`) at its closing `>`, ignoring any
+/// `>` inside quoted literals.
+fn split_declaration(text: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return (&text[..i], &text[i + 1..]),
+            _ => {}
+        }
+    }
+    (text, "")
+}
+
+/// Parse `ENTITY name "value"`, returning `None` for anything else, including
+/// parameter entities and external entities.
+fn parse_entity_declaration(decl: &str) -> Option<(&str, &str)> {
+    let rest = decl.strip_prefix("ENTITY")?;
+    let rest = rest.trim_start();
+    if rest.starts_with('%') {
+        return None;
+    }
+    let name_end = rest.find(char::is_whitespace)?;
+    let (name, rest) = rest.split_at(name_end);
+    let rest = rest.trim_start();
+    let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value_end = rest[1..].find(quote)?;
+    Some((name, &rest[1..1 + value_end]))
+}
@@ -6,6 +6,7 @@ use std::io::{self, Write};
 pub struct EscapingWriter<'a> {
     inner: &'a mut dyn Write,
     escape_quotes: bool,
+    escape_whitespace: bool,
 }
 
 impl<'a> EscapingWriter<'a> {
@@ -15,6 +16,7 @@ impl<'a> EscapingWriter<'a> {
         Self {
             inner,
             escape_quotes: false,
+            escape_whitespace: false,
         }
     }
 
@@ -24,8 +26,16 @@ impl<'a> EscapingWriter<'a> {
         Self {
             inner,
             escape_quotes: true,
+            escape_whitespace: false,
         }
     }
+
+    /// Also escape the whitespace a parser would normalize: `\r` in text,
+    /// and `\t` `\n` `\r` in attribute values.
+    pub fn escape_whitespace(mut self, escape: bool) -> Self {
+        self.escape_whitespace = escape;
+        self
+    }
 }
 
 impl Write for EscapingWriter<'_> {
@@ -36,6 +46,13 @@ impl Write for EscapingWriter<'_> {
                 b'<' => self.inner.write_all(b"&lt;")?,
                 b'>' => self.inner.write_all(b"&gt;")?,
                 b'"' if self.escape_quotes => self.inner.write_all(b"&quot;")?,
+                b'\r' if self.escape_whitespace => self.inner.write_all(b"&#xD;")?,
+                b'\n' if self.escape_whitespace && self.escape_quotes => {
+                    self.inner.write_all(b"&#xA;")?
+                }
+                b'\t' if self.escape_whitespace && self.escape_quotes => {
+                    self.inner.write_all(b"&#x9;")?
+                }
                 _ => self.inner.write_all(&[b])?,
             }
         }
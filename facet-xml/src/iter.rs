@@ -0,0 +1,164 @@
+//! Lazy iteration over repeated elements of a streamed document.
+
+use core::marker::PhantomData;
+use std::io::{BufReader, Read};
+
+use facet_core::Facet;
+use facet_dom::DomDeserializer;
+
+use crate::{DeserializeError, DeserializeOptions, XmlError, XmlParser};
+
+/// Iterate over every element named `tag` in an XML stream, deserializing each
+/// one into an owned `T` as it is reached.
+///
+/// Only one item is held in memory at a time, so this is suited to feeds and data
+/// dumps too large to deserialize into a single `Vec`. Matching elements are found
+/// at any depth; everything around them is skipped. The element name is taken from
+/// `tag`, so `T` does not need to be renamed to match it.
+///
+/// `tag` is a local name, without a namespace prefix: `"entry"` matches both
+/// `<entry>` and `<atom:entry>`. Use [`ElementIter::namespace`] to only match
+/// elements in a given namespace.
+///
+/// After an error, the iterator yields nothing more.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::iter_elements;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Entry {
+///     title: String,
+/// }
+///
+/// let xml = b"<feed><title>News</title><entry><title>a</title></entry><entry><title>b</title></entry></feed>";
+/// let titles: Vec<String> = iter_elements::<Entry, _>(&xml[..], "entry")
+///     .map(|entry| entry.unwrap().title)
+///     .collect();
+/// assert_eq!(titles, ["a", "b"]);
+/// ```
+pub fn iter_elements<T, R>(reader: R, tag: &str) -> ElementIter<T, R>
+where
+    T: Facet<'static>,
+    R: Read,
+{
+    iter_elements_with_options(reader, tag, &DeserializeOptions::default())
+}
+
+/// Like [`iter_elements`], but with custom deserialization options.
+///
+/// [`ParserLimits`](crate::ParserLimits) in `options` apply to the stream as a
+/// whole, not to each item, so they can bound an untrusted feed of any length.
+/// Element names are compared following
+/// [`name_matching`](DeserializeOptions::name_matching).
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::{DeserializeOptions, ParserLimits, iter_elements_with_options};
+///
+/// #[derive(Facet, Debug)]
+/// struct Entry {
+///     title: String,
+/// }
+///
+/// let xml = b"<feed><entry><title>a</title></entry><entry><title>b</title></entry></feed>";
+/// let options = DeserializeOptions::new().limits(ParserLimits::new().max_elements(4));
+/// let mut entries = iter_elements_with_options::<Entry, _>(&xml[..], "entry", &options);
+/// assert_eq!(entries.next().unwrap().unwrap().title, "a");
+/// assert!(entries.next().unwrap().is_err());
+/// ```
+pub fn iter_elements_with_options<T, R>(
+    reader: R,
+    tag: &str,
+    options: &DeserializeOptions,
+) -> ElementIter<T, R>
+where
+    T: Facet<'static>,
+    R: Read,
+{
+    let parser = XmlParser::from_reader(reader).with_options(options);
+    ElementIter {
+        de: DomDeserializer::new_owned(parser),
+        tag: tag.to_string(),
+        namespace: None,
+        done: false,
+        _marker: PhantomData,
+    }
+}
+
+/// Iterator returned by [`iter_elements`] and [`iter_elements_with_options`].
+pub struct ElementIter<T, R: Read> {
+    de: DomDeserializer<'static, false, XmlParser<'static, BufReader<R>>>,
+    tag: String,
+    namespace: Option<String>,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, R: Read> ElementIter<T, R> {
+    /// Only yield elements in the namespace `uri`.
+    ///
+    /// The namespace is compared like a field's `xml::ns`, following
+    /// [`namespace_matching`](DeserializeOptions::namespace_matching): with
+    /// [`NamespaceMatching::Ignore`](crate::NamespaceMatching::Ignore) it has no effect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_xml::iter_elements;
+    ///
+    /// #[derive(Facet, Debug)]
+    /// struct Entry {
+    ///     title: String,
+    /// }
+    ///
+    /// let xml = br#"<feed xmlns:atom="http://www.w3.org/2005/Atom">
+    ///   <entry><title>plain</title></entry>
+    ///   <atom:entry><atom:title>atom</atom:title></atom:entry>
+    /// </feed>"#;
+    /// let titles: Vec<String> = iter_elements::<Entry, _>(&xml[..], "entry")
+    ///     .namespace("http://www.w3.org/2005/Atom")
+    ///     .map(|entry| entry.unwrap().title)
+    ///     .collect();
+    /// assert_eq!(titles, ["atom"]);
+    /// ```
+    pub fn namespace(mut self, uri: impl Into<String>) -> Self {
+        self.namespace = Some(uri.into());
+        self
+    }
+}
+
+impl<T, R> Iterator for ElementIter<T, R>
+where
+    T: Facet<'static>,
+    R: Read,
+{
+    type Item = Result<T, DeserializeError<XmlError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self
+            .de
+            .deserialize_next_named(&self.tag, self.namespace.as_deref())
+        {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<T, R: Read> core::iter::FusedIterator for ElementIter<T, R> where T: Facet<'static> {}
@@ -4,27 +4,42 @@
 #[macro_use]
 mod tracing_macros;
 
+mod canonical;
+mod document;
 mod dom_parser;
+mod encoding;
+mod entities;
 mod escaping;
+mod iter;
 mod serializer;
 
+#[cfg(feature = "tokio")]
+mod async_reader;
+
 #[cfg(feature = "axum")]
 mod axum;
 
-pub use dom_parser::{XmlError, XmlParser};
+pub use canonical::{CanonicalOptions, canonicalize};
+pub use document::{RootElement, XmlDocumentWriter};
+pub use dom_parser::{DeserializeOptions, LimitKind, ParserLimits, XmlError, XmlParser};
+pub use encoding::{Encoding, SliceInput, detect_encoding};
+pub use iter::{ElementIter, iter_elements, iter_elements_with_options};
 
 #[cfg(feature = "axum")]
 pub use axum::{Xml, XmlRejection};
 
 pub use serializer::{
-    FloatFormatter, SerializeOptions, XmlSerializeError, XmlSerializer, to_string,
-    to_string_pretty, to_string_with_options, to_vec, to_vec_with_options,
+    FloatFormatter, SerializeOptions, XmlDeclaration, XmlSerializeError, XmlSerializer,
+    to_fmt_writer, to_fmt_writer_with_options, to_string, to_string_pretty, to_string_with_options,
+    to_vec, to_vec_with_options, to_writer, to_writer_with_options,
 };
 
 // Re-export error types for convenience
 pub use facet_dom::DomDeserializeError as DeserializeError;
+pub use facet_dom::DomDeserializeErrorKind as DeserializeErrorKind;
 pub use facet_dom::DomSerializeError as SerializeError;
 pub use facet_dom::RawMarkup;
+pub use facet_dom::{NameMatching, NamespaceMatching};
 
 /// Deserialize a value from an XML string into an owned type.
 ///
@@ -63,6 +78,12 @@ where
 /// to outlive the result, making it suitable for deserializing from temporary
 /// buffers (e.g., HTTP request bodies).
 ///
+/// Input in UTF-16 (with a byte order mark), or in ISO-8859-1 or Windows-1252
+/// as named by the XML declaration, is transcoded to UTF-8 first. Use
+/// [`detect_encoding`] to find out which encoding a document used. Error spans
+/// of transcoded input are offsets into the UTF-8 text, not into `input`;
+/// line and column positions refer to the document either way.
+///
 /// # Example
 ///
 /// ```
@@ -90,6 +111,195 @@ where
     de.deserialize()
 }
 
+/// Deserialize a value from an XML string into an owned type, with options.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::{self as xml, DeserializeOptions, from_str_with_options};
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Code {
+///     #[facet(xml::text)]
+///     source: String,
+/// }
+///
+/// let options = DeserializeOptions::new().preserve_whitespace(true);
+/// let code: Code = from_str_with_options("<code>  let x = 1;\n</code>", &options).unwrap();
+/// assert_eq!(code.source, "  let x = 1;\n");
+/// ```
+pub fn from_str_with_options<T>(
+    input: &str,
+    options: &DeserializeOptions,
+) -> Result<T, DeserializeError<XmlError>>
+where
+    T: facet_core::Facet<'static>,
+{
+    from_slice_with_options(input.as_bytes(), options)
+}
+
+/// Deserialize a value from XML bytes into an owned type, with options.
+pub fn from_slice_with_options<T>(
+    input: &[u8],
+    options: &DeserializeOptions,
+) -> Result<T, DeserializeError<XmlError>>
+where
+    T: facet_core::Facet<'static>,
+{
+    let parser = XmlParser::new(input).with_options(options);
+    let mut de = facet_dom::DomDeserializer::new_owned(parser);
+    de.deserialize()
+}
+
+/// Deserialize a value from an XML string, reporting every recoverable error
+/// instead of stopping at the first one.
+///
+/// Scalars that fail to parse, denied unknown elements and attributes, and
+/// missing required fields are all reported, each with its path and location.
+/// Any other error, such as malformed XML, ends deserialization and is reported
+/// last.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::from_str_collecting;
+///
+/// #[derive(Facet, Debug)]
+/// #[facet(deny_unknown_fields)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let xml = "<person><age>old</age><nickname>Al</nickname></person>";
+/// let errors = from_str_collecting::<Person>(xml).unwrap_err();
+/// let paths: Vec<_> = errors.iter().map(|e| e.path().unwrap().to_string()).collect();
+/// assert_eq!(paths, ["/person/age[1]", "/person", "/person"]);
+/// ```
+pub fn from_str_collecting<T>(input: &str) -> Result<T, Vec<DeserializeError<XmlError>>>
+where
+    T: facet_core::Facet<'static>,
+{
+    from_slice_collecting(input.as_bytes())
+}
+
+/// Deserialize a value from XML bytes, reporting every recoverable error
+/// instead of stopping at the first one.
+///
+/// See [`from_str_collecting`].
+pub fn from_slice_collecting<T>(input: &[u8]) -> Result<T, Vec<DeserializeError<XmlError>>>
+where
+    T: facet_core::Facet<'static>,
+{
+    from_slice_collecting_with_options(input, &DeserializeOptions::default())
+}
+
+/// Deserialize a value from XML bytes with options, reporting every recoverable
+/// error instead of stopping at the first one.
+///
+/// See [`from_str_collecting`].
+pub fn from_slice_collecting_with_options<T>(
+    input: &[u8],
+    options: &DeserializeOptions,
+) -> Result<T, Vec<DeserializeError<XmlError>>>
+where
+    T: facet_core::Facet<'static>,
+{
+    let parser = XmlParser::new(input).with_options(options);
+    let mut de = facet_dom::DomDeserializer::new_owned(parser);
+    de.deserialize_collecting()
+}
+
+/// Deserialize a value from an XML byte stream into an owned type.
+///
+/// The input is parsed incrementally through a buffered reader, so large
+/// documents do not need to be loaded into memory first. Fields of type
+/// [`RawMarkup`] are not supported in this mode, and errors report byte offsets
+/// rather than line/column positions.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::from_reader;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let xml = b"<person><name>Alice</name><age>30</age></person>";
+/// let person: Person = from_reader(&xml[..]).unwrap();
+/// assert_eq!(person.name, "Alice");
+/// assert_eq!(person.age, 30);
+/// ```
+pub fn from_reader<T, R>(reader: R) -> Result<T, DeserializeError<XmlError>>
+where
+    T: facet_core::Facet<'static>,
+    R: std::io::Read,
+{
+    let parser = XmlParser::from_reader(reader);
+    let mut de = facet_dom::DomDeserializer::new_owned(parser);
+    de.deserialize()
+}
+
+/// Deserialize a value from an asynchronous XML byte stream into an owned type.
+///
+/// The source is parsed without blocking the executor, while the value is
+/// deserialized on tokio's blocking thread pool as the events arrive. Only a
+/// few hundred events are buffered between the two at a time, so memory use
+/// does not grow with the document, apart from the value being built. As with
+/// [`from_reader`], fields of type [`RawMarkup`] are not supported.
+///
+/// Must be called from within a tokio runtime. Requires the `tokio` feature.
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_xml::from_async_reader;
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let xml = b"<person><name>Alice</name><age>30</age></person>";
+/// let person: Person = from_async_reader(&xml[..]).await.unwrap();
+/// assert_eq!(person.name, "Alice");
+/// assert_eq!(person.age, 30);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<T, R>(reader: R) -> Result<T, DeserializeError<XmlError>>
+where
+    T: facet_core::Facet<'static> + Send + 'static,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    from_async_reader_with_options(reader, &DeserializeOptions::default()).await
+}
+
+/// Deserialize a value from an asynchronous XML byte stream into an owned
+/// type, with options.
+///
+/// See [`from_async_reader`]. Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader_with_options<T, R>(
+    reader: R,
+    options: &DeserializeOptions,
+) -> Result<T, DeserializeError<XmlError>>
+where
+    T: facet_core::Facet<'static> + Send + 'static,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    async_reader::deserialize(reader, options).await
+}
+
 /// Deserialize a value from an XML string, allowing borrowing from the input.
 ///
 /// Use this when the deserialized type can borrow from the input string
@@ -108,6 +318,10 @@ where
 /// Use this when the deserialized type can borrow from the input bytes
 /// (e.g., contains `&'a str` fields). The input must outlive the result.
 ///
+/// Text, attribute values and names are borrowed from the input when they
+/// contain no entity references; values that need decoding, and input that was
+/// transcoded from another encoding, can only fill owned or `Cow` fields.
+///
 /// For most use cases, prefer [`from_slice`] which produces owned types.
 pub fn from_slice_borrowed<'input, T>(input: &'input [u8]) -> Result<T, DeserializeError<XmlError>>
 where
@@ -166,6 +380,23 @@ facet::define_attr_grammar! {
         /// This sets the default namespace for all fields that don't have their own
         /// `xml::ns` attribute. Individual fields can override this with `xml::ns`.
         NsAll(&'static str),
+        /// Specifies the prefix to serialize a namespace with.
+        ///
+        /// Usage: `#[facet(xml::prefix = "soap")]`
+        ///
+        /// On a field, this is the prefix for the field's `xml::ns` namespace. On a
+        /// container with `xml::ns_all`, its element and fields use the prefix
+        /// instead of a default namespace declaration. The prefix is not used if
+        /// another namespace already has it. Deserialization matches by namespace
+        /// URI and ignores prefixes.
+        Prefix(&'static str),
+        /// Another name the field is deserialized from. Can be given more than once.
+        ///
+        /// Usage: `#[facet(xml::alias = "UserName", xml::alias = "user_name")]`
+        ///
+        /// Aliases are matched as written, like `#[facet(alias = "...")]`, which
+        /// only keeps a single alias. Serialization uses the field's own name.
+        Alias(&'static str),
         /// Marks an enum variant as a catch-all for unknown XML elements.
         ///
         /// Usage: `#[facet(xml::custom_element)]`
@@ -183,5 +414,59 @@ facet::define_attr_grammar! {
         ///
         /// The field type should be `Option<String>` to handle documents without DOCTYPE.
         Doctype,
+        /// Marks a text or string element field as serialized in a CDATA section.
+        ///
+        /// Usage: `#[facet(xml::cdata)]`
+        ///
+        /// The value is written as `<![CDATA[...]]>` instead of being entity-escaped,
+        /// which keeps embedded markup readable. Any `]]>` in the value is split
+        /// across two sections. Deserialization accepts both CDATA and escaped text.
+        Cdata,
+        /// Marks a field as collecting the comments among an element's children.
+        ///
+        /// Usage: `#[facet(xml::comment)]`
+        ///
+        /// When serializing, the comments are emitted together at the field's position
+        /// among the children. Where each comment was in the source is not recorded,
+        /// so a comment read after a child element is written back before it if the
+        /// field is declared first. Declare the field where its comments belong, or
+        /// use [`RawMarkup`] to keep an element exactly as written. Add
+        /// [`Attr::Prolog`] for the comments before the root element.
+        ///
+        /// The field type should be `Vec<String>`; a single `String` keeps the last comment.
+        Comment,
+        /// Marks a field as collecting processing instructions, like `<?xml-stylesheet ...?>`.
+        ///
+        /// Usage: `#[facet(xml::pi)]`
+        ///
+        /// Processing instructions are collected and emitted like [`Attr::Comment`].
+        /// Each item is either a struct with `target` and `data` string fields, or
+        /// a string holding the target, a space, and the data.
+        Pi,
+        /// Makes an `xml::comment` or `xml::pi` field hold the items before the
+        /// root element instead of those among its children.
+        ///
+        /// Usage: `#[facet(xml::comment, xml::prolog)]`
+        ///
+        /// When serializing, the items are emitted before the root element. Like
+        /// [`Attr::Doctype`], this only applies to the root struct; elsewhere the
+        /// field stays empty and is not serialized.
+        Prolog,
+        /// Makes attributes that no field accepts an error, while unknown child
+        /// elements are still skipped.
+        ///
+        /// Usage: `#[facet(xml::deny_unknown_attributes)]`
+        ///
+        /// `#[facet(deny_unknown_fields)]` rejects both. Can be overridden per call
+        /// with [`DeserializeOptions::deny_unknown_attributes`].
+        DenyUnknownAttributes,
+        /// Makes child elements that no field accepts an error, while unknown
+        /// attributes such as `xml:lang` or `data-*` are still ignored.
+        ///
+        /// Usage: `#[facet(xml::deny_unknown_elements)]`
+        ///
+        /// `#[facet(deny_unknown_fields)]` rejects both. Can be overridden per call
+        /// with [`DeserializeOptions::deny_unknown_elements`].
+        DenyUnknownElements,
     }
 }
@@ -1,13 +1,15 @@
 extern crate alloc;
 
 use alloc::{borrow::Cow, format, string::String, vec::Vec};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 use facet_core::{Def, Facet, ScalarType};
 use facet_dom::{DomSerializeError, DomSerializer};
 use facet_reflect::Peek;
 
+use crate::canonical::{CanonicalOptions, canonicalize};
+use crate::document::RootElement;
 use crate::escaping::EscapingWriter;
 
 pub use facet_dom::FloatFormatter;
@@ -135,6 +137,34 @@ pub struct SerializeOptions {
     ///
     /// Default: `false` (all `&` characters are escaped to `&amp;`).
     pub preserve_entities: bool,
+    /// XML declaration written at the start of the document (default: none)
+    pub declaration: Option<XmlDeclaration>,
+    /// Processing instructions (target, data) written before the root element,
+    /// after the XML declaration (default: none)
+    pub processing_instructions: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+    /// Prefixes to use for namespace URIs, keyed by URI (default: none)
+    ///
+    /// These take precedence over the well-known and generated prefixes,
+    /// but not over `xml::prefix` attributes.
+    pub namespace_prefixes: HashMap<Cow<'static, str>, Cow<'static, str>>,
+    /// Whether to declare all namespaces once on the root element (default: false)
+    ///
+    /// Otherwise each element declares the namespaces it uses. See
+    /// [`SerializeOptions::hoist_namespaces`] for how they are found.
+    pub hoist_namespaces: bool,
+    /// Canonicalize the output as Canonical XML or Exclusive XML Canonicalization (default: none)
+    ///
+    /// Pretty-printing is ignored. The document is serialized, then parsed back and
+    /// canonicalized as a whole, so it is held in memory until the root element is
+    /// closed: even [`to_writer`] and [`XmlSerializer::from_writer`] write nothing
+    /// before then, and need memory for the entire document.
+    pub canonical: Option<CanonicalOptions>,
+    /// Whether to write elements without children or text as `<tag/>` (default: false)
+    pub self_close_empty: bool,
+    /// Elements written as `<tag/>` when empty, even without `self_close_empty` (default: none)
+    ///
+    /// Matched against the element name without prefix.
+    pub void_elements: Vec<Cow<'static, str>>,
 }
 
 impl Default for SerializeOptions {
@@ -144,6 +174,13 @@ impl Default for SerializeOptions {
             indent: Cow::Borrowed("  "),
             float_formatter: None,
             preserve_entities: false,
+            declaration: None,
+            processing_instructions: Vec::new(),
+            namespace_prefixes: HashMap::new(),
+            hoist_namespaces: false,
+            canonical: None,
+            self_close_empty: false,
+            void_elements: Vec::new(),
         }
     }
 }
@@ -155,6 +192,13 @@ impl core::fmt::Debug for SerializeOptions {
             .field("indent", &self.indent)
             .field("float_formatter", &self.float_formatter.map(|_| "..."))
             .field("preserve_entities", &self.preserve_entities)
+            .field("declaration", &self.declaration)
+            .field("processing_instructions", &self.processing_instructions)
+            .field("namespace_prefixes", &self.namespace_prefixes)
+            .field("hoist_namespaces", &self.hoist_namespaces)
+            .field("canonical", &self.canonical)
+            .field("self_close_empty", &self.self_close_empty)
+            .field("void_elements", &self.void_elements)
             .finish()
     }
 }
@@ -227,6 +271,209 @@ impl SerializeOptions {
         self.preserve_entities = preserve;
         self
     }
+
+    /// Write an XML declaration at the start of the document.
+    ///
+    /// It comes before any processing instructions and the DOCTYPE from an
+    /// `xml::doctype` field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use facet::Facet;
+    /// # use facet_xml::{to_string_with_options, SerializeOptions, XmlDeclaration};
+    /// #[derive(Facet)]
+    /// struct Note {
+    ///     body: String,
+    /// }
+    ///
+    /// let options = SerializeOptions::new()
+    ///     .declaration(XmlDeclaration::new().standalone(true))
+    ///     .processing_instruction("xml-stylesheet", r#"href="note.css""#);
+    /// let xml = to_string_with_options(&Note { body: "hi".into() }, &options).unwrap();
+    /// assert_eq!(
+    ///     xml,
+    ///     r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><?xml-stylesheet href="note.css"?><note><body>hi</body></note>"#
+    /// );
+    /// ```
+    pub fn declaration(mut self, declaration: XmlDeclaration) -> Self {
+        self.declaration = Some(declaration);
+        self
+    }
+
+    /// Add a processing instruction to write before the root element.
+    pub fn processing_instruction(
+        mut self,
+        target: impl Into<Cow<'static, str>>,
+        data: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.processing_instructions
+            .push((target.into(), data.into()));
+        self
+    }
+
+    /// Set the prefixes to use for namespace URIs, as (URI, prefix) pairs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use facet::Facet;
+    /// # use facet_xml as xml;
+    /// # use facet_xml::{to_string_with_options, SerializeOptions};
+    /// #[derive(Facet)]
+    /// #[facet(xml::ns_all = "http://schemas.xmlsoap.org/soap/envelope/")]
+    /// struct Envelope {
+    ///     #[facet(xml::ns = "urn:example:orders")]
+    ///     order: String,
+    /// }
+    ///
+    /// let options = SerializeOptions::new().namespace_prefixes([("urn:example:orders", "ord")]);
+    /// let xml = to_string_with_options(&Envelope { order: "42".into() }, &options).unwrap();
+    /// assert_eq!(
+    ///     xml,
+    ///     r#"<envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/"><ord:order xmlns:ord="urn:example:orders">42</ord:order></envelope>"#
+    /// );
+    /// ```
+    pub fn namespace_prefixes<U, P>(mut self, prefixes: impl IntoIterator<Item = (U, P)>) -> Self
+    where
+        U: Into<Cow<'static, str>>,
+        P: Into<Cow<'static, str>>,
+    {
+        self.namespace_prefixes.extend(
+            prefixes
+                .into_iter()
+                .map(|(uri, prefix)| (uri.into(), prefix.into())),
+        );
+        self
+    }
+
+    /// Declare all namespaces once on the root element instead of on each element using them.
+    ///
+    /// The `to_*` functions find the namespaces with a first pass over the value
+    /// that discards its output, so serializing takes about twice as long but
+    /// output is still streamed. An [`XmlDocumentWriter`](crate::XmlDocumentWriter)
+    /// cannot look ahead at its items: namespaces declared on its
+    /// [`RootElement`] are in scope for all of them, and any others are declared
+    /// where they are used, as they are when an [`XmlSerializer`] is driven directly.
+    pub const fn hoist_namespaces(mut self, hoist: bool) -> Self {
+        self.hoist_namespaces = hoist;
+        self
+    }
+
+    /// Write the canonical form of the document, for signing or comparing it.
+    ///
+    /// The whole document is built in memory before anything is written, since
+    /// it is parsed back to be canonicalized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use facet::Facet;
+    /// # use facet_xml as xml;
+    /// use facet_xml::{CanonicalOptions, SerializeOptions};
+    ///
+    /// #[derive(Facet)]
+    /// struct Order {
+    ///     #[facet(xml::attribute)]
+    ///     status: String,
+    ///     #[facet(xml::attribute)]
+    ///     id: u32,
+    ///     note: Option<String>,
+    /// }
+    ///
+    /// let order = Order { status: "open".into(), id: 7, note: Some(String::new()) };
+    /// let options = SerializeOptions::new().canonical(CanonicalOptions::new());
+    /// let xml = xml::to_string_with_options(&order, &options).unwrap();
+    /// assert_eq!(xml, r#"<order id="7" status="open"><note></note></order>"#);
+    /// ```
+    pub fn canonical(mut self, options: CanonicalOptions) -> Self {
+        self.canonical = Some(options);
+        self
+    }
+
+    /// Write elements without children or text as `<tag/>` instead of `<tag></tag>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use facet::Facet;
+    /// # use facet_xml as xml;
+    /// use facet_xml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Link {
+    ///     #[facet(xml::attribute)]
+    ///     href: String,
+    /// }
+    ///
+    /// let link = Link { href: "/feed".into() };
+    /// let options = SerializeOptions::new().self_close_empty(true);
+    /// let xml = xml::to_string_with_options(&link, &options).unwrap();
+    /// assert_eq!(xml, r#"<link href="/feed"/>"#);
+    /// ```
+    pub const fn self_close_empty(mut self, self_close: bool) -> Self {
+        self.self_close_empty = self_close;
+        self
+    }
+
+    /// Set the elements written as `<tag/>` when empty, like the void elements of HTML.
+    ///
+    /// Other elements keep their end tag unless `self_close_empty` is set.
+    pub fn void_elements<N>(mut self, names: impl IntoIterator<Item = N>) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.void_elements = names.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// The XML declaration, `<?xml version="1.0" encoding="UTF-8"?>`.
+///
+/// The encoding is only written as a label: the serializer always produces UTF-8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XmlDeclaration {
+    /// The `version` pseudo-attribute (default: "1.0")
+    pub version: Cow<'static, str>,
+    /// The `encoding` pseudo-attribute, omitted if `None` (default: "UTF-8")
+    pub encoding: Option<Cow<'static, str>>,
+    /// The `standalone` pseudo-attribute, omitted if `None` (default: none)
+    pub standalone: Option<bool>,
+}
+
+impl Default for XmlDeclaration {
+    fn default() -> Self {
+        Self {
+            version: Cow::Borrowed("1.0"),
+            encoding: Some(Cow::Borrowed("UTF-8")),
+            standalone: None,
+        }
+    }
+}
+
+impl XmlDeclaration {
+    /// Create a declaration for XML 1.0 in UTF-8.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the version.
+    pub fn version(mut self, version: impl Into<Cow<'static, str>>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Set the encoding label, or omit it with `None`.
+    pub fn encoding(mut self, encoding: Option<impl Into<Cow<'static, str>>>) -> Self {
+        self.encoding = encoding.map(Into::into);
+        self
+    }
+
+    /// Set the standalone flag (`yes` for `true`).
+    pub const fn standalone(mut self, standalone: bool) -> Self {
+        self.standalone = Some(standalone);
+        self
+    }
 }
 
 /// Well-known XML namespace URIs and their conventional prefixes.
@@ -246,6 +493,22 @@ const WELL_KNOWN_NAMESPACES: &[(&str, &str)] = &[
 #[derive(Debug)]
 pub struct XmlSerializeError {
     msg: Cow<'static, str>,
+    /// The error from the output sink, if writing to it failed
+    io: Option<std::io::Error>,
+}
+
+impl XmlSerializeError {
+    fn io(error: std::io::Error) -> Self {
+        Self {
+            msg: Cow::Owned(format!("write error: {error}")),
+            io: Some(error),
+        }
+    }
+
+    /// Returns the I/O error from the output sink, if writing to it failed.
+    pub fn io_error(&self) -> Option<&std::io::Error> {
+        self.io.as_ref()
+    }
 }
 
 impl core::fmt::Display for XmlSerializeError {
@@ -254,7 +517,11 @@ impl core::fmt::Display for XmlSerializeError {
     }
 }
 
-impl std::error::Error for XmlSerializeError {}
+impl std::error::Error for XmlSerializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.io.as_ref().map(|e| e as _)
+    }
+}
 
 /// XML serializer with configurable output options.
 ///
@@ -262,12 +529,24 @@ impl std::error::Error for XmlSerializeError {}
 /// - structs are elements whose children are field elements
 /// - sequences are elements whose children are repeated `<item>` elements
 /// - element names are treated as map keys; the root element name is ignored
-pub struct XmlSerializer {
+pub struct XmlSerializer<W = Vec<u8>> {
+    /// Output buffered until it is flushed to `writer`
     out: Vec<u8>,
+    /// Sink the output is flushed to
+    writer: W,
+    /// Buffer size at which output is flushed to `writer`
+    flush_threshold: usize,
     /// Stack of element names for closing tags
     element_stack: Vec<String>,
     /// Namespace URI -> prefix mapping for already-declared namespaces.
     declared_namespaces: HashMap<String, String>,
+    /// Namespace URIs declared on the document root, in scope for all output.
+    root_namespaces: HashSet<String>,
+    /// Namespace declarations (prefix, URI) hoisted to the root element: found
+    /// in a first pass, then written on the root start tag in the second
+    hoisted_namespaces: Vec<(String, String)>,
+    /// Whether this is the first pass when hoisting, which only finds namespaces
+    finding_namespaces: bool,
     /// Counter for auto-generating namespace prefixes (ns0, ns1, ...).
     next_ns_index: usize,
     /// The currently active default namespace (from xmlns="..." on an ancestor).
@@ -275,6 +554,8 @@ pub struct XmlSerializer {
     current_default_ns: Option<String>,
     /// Container-level default namespace (from xml::ns_all) for current struct
     current_ns_all: Option<String>,
+    /// Container-level prefix (from xml::prefix) for the current struct's namespace
+    current_prefix_all: Option<String>,
     /// True if the current field is an attribute (vs element)
     pending_is_attribute: bool,
     /// True if the current field is text content (xml::text)
@@ -285,18 +566,37 @@ pub struct XmlSerializer {
     pending_is_doctype: bool,
     /// True if the current field is a tag field (xml::tag)
     pending_is_tag: bool,
+    /// True if the current field's text is written as CDATA (xml::cdata)
+    pending_is_cdata: bool,
+    /// True if the current field collects comments (xml::comment)
+    pending_is_comment: bool,
+    /// True if the current field collects processing instructions (xml::pi)
+    pending_is_pi: bool,
+    /// True if the current comment or PI field holds the prolog (xml::prolog)
+    pending_is_prolog: bool,
     /// Pending namespace for the next field
     pending_namespace: Option<String>,
+    /// Pending prefix for the next field's namespace (xml::prefix)
+    pending_prefix: Option<String>,
     /// Serialization options (pretty-printing, float formatting, etc.)
     options: SerializeOptions,
     /// Current indentation depth for pretty-printing
     depth: usize,
+    /// True once the declaration and processing instructions from the options are written
+    prolog_written: bool,
     /// True if we're collecting attributes (between element_start and children_start)
     collecting_attributes: bool,
+    /// True if the current element is written as `<tag/>` when it turns out empty
+    may_self_close: bool,
+    /// True if the `>` of the current start tag is held back until its content is known
+    start_tag_open: bool,
     /// True if the next element should establish a default namespace (from ns_all)
     pending_establish_default_ns: bool,
 }
 
+/// Size of the output buffer kept before flushing to a writer.
+const WRITER_BUFFER_SIZE: usize = 8 * 1024;
+
 impl XmlSerializer {
     /// Create a new XML serializer with default options.
     pub fn new() -> Self {
@@ -305,46 +605,169 @@ impl XmlSerializer {
 
     /// Create a new XML serializer with the given options.
     pub fn with_options(options: SerializeOptions) -> Self {
+        // Everything stays in the buffer until finish()
+        Self::build(Vec::new(), usize::MAX, options)
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        let mut bytes = self.writer;
+        if bytes.is_empty() {
+            return self.out;
+        }
+        bytes.extend_from_slice(&self.out);
+        bytes
+    }
+}
+
+impl<W: Write> XmlSerializer<W> {
+    /// Create an XML serializer that writes incrementally to `writer`.
+    ///
+    /// Output is buffered internally; call [`into_inner`](Self::into_inner)
+    /// afterwards to flush the rest. Canonical output is only written once the
+    /// root element is closed.
+    pub fn from_writer(writer: W) -> Self {
+        Self::from_writer_with_options(writer, SerializeOptions::default())
+    }
+
+    /// Create an XML serializer with the given options that writes incrementally to `writer`.
+    pub fn from_writer_with_options(writer: W, options: SerializeOptions) -> Self {
+        Self::build(writer, WRITER_BUFFER_SIZE, options)
+    }
+
+    /// Flush the buffered output and return the writer.
+    pub fn into_inner(mut self) -> Result<W, XmlSerializeError> {
+        self.flush_buffer()?;
+        self.writer.flush().map_err(XmlSerializeError::io)?;
+        Ok(self.writer)
+    }
+
+    /// Open the root element of a document written item by item.
+    ///
+    /// Namespaces declared here stay in scope for everything written inside it,
+    /// so items reuse them instead of declaring them again.
+    pub(crate) fn open_root(&mut self, root: &RootElement) -> Result<(), XmlSerializeError> {
+        self.write_prolog()?;
+        self.write_indent();
+        self.out.push(b'<');
+        self.out.extend_from_slice(root.name.as_bytes());
+
+        if let Some(uri) = &root.default_namespace {
+            self.write_raw_attribute("xmlns", uri);
+            self.current_default_ns = Some(uri.to_string());
+        }
+        for (prefix, uri) in &root.namespaces {
+            self.write_raw_attribute(&format!("xmlns:{prefix}"), uri);
+            self.declared_namespaces
+                .insert(uri.to_string(), prefix.to_string());
+            self.root_namespaces.insert(uri.to_string());
+        }
+        for (name, value) in &root.attributes {
+            self.write_raw_attribute(name, value);
+        }
+
+        self.element_stack.push(root.name.to_string());
+        self.write_element_tag_end();
+        self.flush_if_full()
+    }
+
+    /// Write an attribute with a string value: ` name="escaped_value"`
+    fn write_raw_attribute(&mut self, name: &str, value: &str) {
+        self.out.push(b' ');
+        self.out.extend_from_slice(name.as_bytes());
+        self.out.extend_from_slice(b"=\"");
+        let _ = EscapingWriter::attribute(&mut self.out)
+            .escape_whitespace(self.options.canonical.is_some())
+            .write_all(value.as_bytes());
+        self.out.push(b'"');
+    }
+
+    /// Write the buffered output to the writer.
+    fn flush_buffer(&mut self) -> Result<(), XmlSerializeError> {
+        self.writer
+            .write_all(&self.out)
+            .map_err(XmlSerializeError::io)?;
+        self.out.clear();
+        Ok(())
+    }
+
+    /// Flush the buffered output once it is over the threshold.
+    ///
+    /// Only called between writes of whole strings, so what is flushed is valid UTF-8.
+    fn flush_if_full(&mut self) -> Result<(), XmlSerializeError> {
+        if self.out.len() >= self.flush_threshold && self.options.canonical.is_none() {
+            self.flush_buffer()?;
+        }
+        Ok(())
+    }
+
+    fn build(writer: W, flush_threshold: usize, mut options: SerializeOptions) -> Self {
+        if options.canonical.is_some() {
+            options.pretty = false;
+        }
         Self {
             out: Vec::new(),
+            writer,
+            flush_threshold,
             element_stack: Vec::new(),
             declared_namespaces: HashMap::new(),
+            root_namespaces: HashSet::new(),
+            hoisted_namespaces: Vec::new(),
+            finding_namespaces: false,
             next_ns_index: 0,
             current_default_ns: None,
             current_ns_all: None,
+            current_prefix_all: None,
             pending_is_attribute: false,
             pending_is_text: false,
             pending_is_elements: false,
             pending_is_doctype: false,
             pending_is_tag: false,
+            pending_is_cdata: false,
+            pending_is_comment: false,
+            pending_is_pi: false,
+            pending_is_prolog: false,
             pending_namespace: None,
+            pending_prefix: None,
             options,
             depth: 0,
+            prolog_written: false,
             collecting_attributes: false,
+            may_self_close: false,
+            start_tag_open: false,
             pending_establish_default_ns: false,
         }
     }
 
-    pub fn finish(self) -> Vec<u8> {
-        self.out
-    }
-
     /// Write the opening part of an element tag: `<tag` (without the closing `>`)
     /// This allows attributes to be written directly afterwards.
-    fn write_element_tag_start(&mut self, name: &str, namespace: Option<&str>) {
+    ///
+    /// `prefix` is the prefix requested with `xml::prefix` for the namespace.
+    fn write_element_tag_start(
+        &mut self,
+        name: &str,
+        namespace: Option<&str>,
+        prefix: Option<&str>,
+    ) {
         self.write_indent();
         self.out.push(b'<');
 
+        let is_root = self.element_stack.is_empty();
+        // When hoisting, only the root may declare a default namespace
+        let may_establish_default = is_root || !self.options.hoist_namespaces;
+
         // Track the close tag (may include prefix)
         let close_tag: String;
 
         // Handle namespace for element
         if let Some(ns_uri) = namespace {
-            if self.current_default_ns.as_deref() == Some(ns_uri) {
-                // Element is in the current default namespace - use unprefixed form
+            if prefix.is_none() && self.current_default_ns.as_deref() == Some(ns_uri) {
+                // Element is in the current default namespace - use unprefixed form.
+                // A struct root with ns_all has nothing left to establish.
+                self.pending_establish_default_ns = false;
                 self.out.extend_from_slice(name.as_bytes());
                 close_tag = name.to_string();
-            } else if self.pending_establish_default_ns {
+            } else if prefix.is_none() && self.pending_establish_default_ns && may_establish_default
+            {
                 // This is a struct root with ns_all - establish as default namespace
                 self.out.extend_from_slice(name.as_bytes());
                 self.out.extend_from_slice(b" xmlns=\"");
@@ -354,17 +777,13 @@ impl XmlSerializer {
                 self.pending_establish_default_ns = false;
                 close_tag = name.to_string();
             } else {
-                // Field-level namespace - use prefix
-                let prefix = self.get_or_create_prefix(ns_uri);
+                // Field-level namespace, or one with a requested prefix - use prefix
+                self.pending_establish_default_ns = false;
+                let prefix = self.prefix_for(ns_uri, prefix);
                 self.out.extend_from_slice(prefix.as_bytes());
                 self.out.push(b':');
                 self.out.extend_from_slice(name.as_bytes());
-                // Write xmlns declaration for this prefix
-                self.out.extend_from_slice(b" xmlns:");
-                self.out.extend_from_slice(prefix.as_bytes());
-                self.out.extend_from_slice(b"=\"");
-                self.out.extend_from_slice(ns_uri.as_bytes());
-                self.out.push(b'"');
+                self.write_namespace_declaration(&prefix, ns_uri);
                 close_tag = format!("{}:{}", prefix, name);
             }
         } else {
@@ -372,10 +791,59 @@ impl XmlSerializer {
             close_tag = name.to_string();
         }
 
+        if is_root && !self.finding_namespaces {
+            for (prefix, uri) in core::mem::take(&mut self.hoisted_namespaces) {
+                self.write_raw_attribute(&format!("xmlns:{prefix}"), &uri);
+            }
+        }
+
         // Push the close tag for element_end
         self.element_stack.push(close_tag);
     }
 
+    /// Write ` xmlns:prefix="uri"` on the element being written, unless it is in scope already.
+    ///
+    /// When finding namespaces to hoist, the declaration is recorded for the root element instead.
+    fn write_namespace_declaration(&mut self, prefix: &str, namespace_uri: &str) {
+        if self.root_namespaces.contains(namespace_uri) {
+            return;
+        }
+        if self.finding_namespaces {
+            self.root_namespaces.insert(namespace_uri.to_string());
+            self.hoisted_namespaces
+                .push((prefix.to_string(), namespace_uri.to_string()));
+            return;
+        }
+        let declaration = format!(" xmlns:{prefix}=\"{namespace_uri}\"");
+        self.out.extend_from_slice(declaration.as_bytes());
+    }
+
+    /// Serialize `value`, first finding the namespaces to declare on the root
+    /// element when hoisting them.
+    fn serialize_value(
+        &mut self,
+        value: Peek<'_, '_>,
+    ) -> Result<(), DomSerializeError<XmlSerializeError>> {
+        if self.options.hoist_namespaces {
+            let options = SerializeOptions {
+                pretty: false,
+                canonical: None,
+                ..self.options.clone()
+            };
+            let mut finder = XmlSerializer::build(std::io::sink(), WRITER_BUFFER_SIZE, options);
+            finder.finding_namespaces = true;
+            facet_dom::serialize(&mut finder, value)?;
+
+            for (prefix, uri) in &finder.hoisted_namespaces {
+                self.declared_namespaces.insert(uri.clone(), prefix.clone());
+                self.root_namespaces.insert(uri.clone());
+            }
+            self.next_ns_index = finder.next_ns_index;
+            self.hoisted_namespaces = finder.hoisted_namespaces;
+        }
+        facet_dom::serialize(self, value)
+    }
+
     /// Write an attribute directly to the output: ` name="escaped_value"`
     /// Returns Ok(true) if written, Ok(false) if value wasn't a scalar (attribute skipped).
     fn write_attribute(
@@ -383,11 +851,13 @@ impl XmlSerializer {
         name: &str,
         value: Peek<'_, '_>,
         namespace: Option<&str>,
+        prefix: Option<&str>,
     ) -> std::io::Result<bool> {
         // First, write the value to a temporary buffer to check if it's a scalar
         let mut value_buf = Vec::new();
         let written = write_scalar_value(
-            &mut EscapingWriter::attribute(&mut value_buf),
+            &mut EscapingWriter::attribute(&mut value_buf)
+                .escape_whitespace(self.options.canonical.is_some()),
             value,
             self.options.float_formatter,
         )?;
@@ -398,18 +868,15 @@ impl XmlSerializer {
         }
 
         // Now write the attribute
-        self.out.push(b' ');
         if let Some(ns_uri) = namespace {
-            let prefix = self.get_or_create_prefix(ns_uri);
-            // Write xmlns declaration
-            self.out.extend_from_slice(b"xmlns:");
-            self.out.extend_from_slice(prefix.as_bytes());
-            self.out.extend_from_slice(b"=\"");
-            self.out.extend_from_slice(ns_uri.as_bytes());
-            self.out.extend_from_slice(b"\" ");
+            let prefix = self.prefix_for(ns_uri, prefix);
+            self.write_namespace_declaration(&prefix, ns_uri);
             // Write prefixed attribute
+            self.out.push(b' ');
             self.out.extend_from_slice(prefix.as_bytes());
             self.out.push(b':');
+        } else {
+            self.out.push(b' ');
         }
         self.out.extend_from_slice(name.as_bytes());
         self.out.extend_from_slice(b"=\"");
@@ -418,6 +885,15 @@ impl XmlSerializer {
         Ok(true)
     }
 
+    /// Write the `>` held back for an element that may self-close, now that
+    /// it has content.
+    fn close_start_tag(&mut self) {
+        if self.start_tag_open {
+            self.start_tag_open = false;
+            self.write_element_tag_end();
+        }
+    }
+
     /// Finish the element opening tag by writing `>` and incrementing depth.
     fn write_element_tag_end(&mut self) {
         self.out.push(b'>');
@@ -441,8 +917,93 @@ impl XmlSerializer {
             self.out.extend_from_slice(escaped.as_bytes());
         } else {
             // Use EscapingWriter for consistency with attribute escaping
-            let _ = EscapingWriter::text(&mut self.out).write_all(text.as_bytes());
+            let _ = EscapingWriter::text(&mut self.out)
+                .escape_whitespace(self.options.canonical.is_some())
+                .write_all(text.as_bytes());
+        }
+    }
+
+    /// Write text as a CDATA section.
+    ///
+    /// A `]]>` in the text would end the section early, so it is split across
+    /// two sections: `]]` ends the first and `>` starts the second.
+    fn write_cdata(&mut self, text: &str) {
+        self.out.extend_from_slice(b"<![CDATA[");
+        let mut parts = text.split("]]>");
+        if let Some(first) = parts.next() {
+            self.out.extend_from_slice(first.as_bytes());
+        }
+        for part in parts {
+            self.out.extend_from_slice(b"]]]]><![CDATA[>");
+            self.out.extend_from_slice(part.as_bytes());
         }
+        self.out.extend_from_slice(b"]]>");
+    }
+
+    /// Write the XML declaration and processing instructions from the options,
+    /// once, before anything else.
+    fn write_prolog(&mut self) -> Result<(), XmlSerializeError> {
+        if self.prolog_written {
+            return Ok(());
+        }
+        self.prolog_written = true;
+
+        if let Some(declaration) = self.options.declaration.take() {
+            self.out.extend_from_slice(b"<?xml version=\"");
+            self.out.extend_from_slice(declaration.version.as_bytes());
+            self.out.push(b'"');
+            if let Some(encoding) = &declaration.encoding {
+                self.out.extend_from_slice(b" encoding=\"");
+                self.out.extend_from_slice(encoding.as_bytes());
+                self.out.push(b'"');
+            }
+            if let Some(standalone) = declaration.standalone {
+                self.out.extend_from_slice(if standalone {
+                    b" standalone=\"yes\""
+                } else {
+                    b" standalone=\"no\""
+                });
+            }
+            self.out.extend_from_slice(b"?>");
+            self.write_newline();
+        }
+
+        for (target, data) in core::mem::take(&mut self.options.processing_instructions) {
+            self.write_processing_instruction(&target, &data)?;
+        }
+        Ok(())
+    }
+
+    /// Write a processing instruction: `<?target data?>`
+    fn write_processing_instruction(
+        &mut self,
+        target: &str,
+        data: &str,
+    ) -> Result<(), XmlSerializeError> {
+        if target.is_empty() || target.eq_ignore_ascii_case("xml") {
+            return Err(XmlSerializeError {
+                msg: Cow::Owned(format!("invalid processing instruction target: {target:?}")),
+                io: None,
+            });
+        }
+        if data.contains("?>") {
+            return Err(XmlSerializeError {
+                msg: Cow::Owned(format!(
+                    "processing instruction data cannot contain `?>`: {data:?}"
+                )),
+                io: None,
+            });
+        }
+        self.write_indent();
+        self.out.extend_from_slice(b"<?");
+        self.out.extend_from_slice(target.as_bytes());
+        if !data.is_empty() {
+            self.out.push(b' ');
+            self.out.extend_from_slice(data.as_bytes());
+        }
+        self.out.extend_from_slice(b"?>");
+        self.write_newline();
+        Ok(())
     }
 
     /// Write indentation for the current depth (if pretty-printing is enabled).
@@ -461,6 +1022,25 @@ impl XmlSerializer {
         }
     }
 
+    /// Get the prefix for a namespace URI, preferring the one requested with `xml::prefix`.
+    ///
+    /// The requested prefix is not used if another namespace has it, or if the
+    /// namespace is already declared on the root with a different prefix.
+    fn prefix_for(&mut self, namespace_uri: &str, requested: Option<&str>) -> String {
+        if let Some(requested) = requested {
+            let taken = self
+                .declared_namespaces
+                .iter()
+                .any(|(uri, prefix)| prefix == requested && uri != namespace_uri);
+            if !taken && !self.root_namespaces.contains(namespace_uri) {
+                self.declared_namespaces
+                    .insert(namespace_uri.to_string(), requested.to_string());
+                return requested.to_string();
+            }
+        }
+        self.get_or_create_prefix(namespace_uri)
+    }
+
     /// Get or create a prefix for the given namespace URI.
     fn get_or_create_prefix(&mut self, namespace_uri: &str) -> String {
         // Check if we've already assigned a prefix to this URI
@@ -468,11 +1048,18 @@ impl XmlSerializer {
             return prefix.clone();
         }
 
-        // Try well-known namespaces
-        let prefix = WELL_KNOWN_NAMESPACES
-            .iter()
-            .find(|(uri, _)| *uri == namespace_uri)
-            .map(|(_, prefix)| (*prefix).to_string())
+        // Try prefixes from the options, then well-known namespaces
+        let prefix = self
+            .options
+            .namespace_prefixes
+            .get(namespace_uri)
+            .map(|prefix| prefix.to_string())
+            .or_else(|| {
+                WELL_KNOWN_NAMESPACES
+                    .iter()
+                    .find(|(uri, _)| *uri == namespace_uri)
+                    .map(|(_, prefix)| (*prefix).to_string())
+            })
             .unwrap_or_else(|| {
                 // Auto-generate a prefix
                 let prefix = format!("ns{}", self.next_ns_index);
@@ -500,7 +1087,12 @@ impl XmlSerializer {
         self.pending_is_elements = false;
         self.pending_is_doctype = false;
         self.pending_is_tag = false;
+        self.pending_is_cdata = false;
+        self.pending_is_comment = false;
+        self.pending_is_pi = false;
+        self.pending_is_prolog = false;
         self.pending_namespace = None;
+        self.pending_prefix = None;
     }
 }
 
@@ -510,19 +1102,31 @@ impl Default for XmlSerializer {
     }
 }
 
-impl DomSerializer for XmlSerializer {
+impl<W: Write> DomSerializer for XmlSerializer<W> {
     type Error = XmlSerializeError;
 
     fn element_start(&mut self, tag: &str, namespace: Option<&str>) -> Result<(), Self::Error> {
         // Priority: explicit namespace > pending_namespace > current_ns_all (for struct roots)
+        let mut prefix = self.pending_prefix.take();
         let ns = namespace
             .map(|s| s.to_string())
-            .or_else(|| self.pending_namespace.take())
-            .or_else(|| self.current_ns_all.clone());
+            .or_else(|| self.pending_namespace.take());
+        let ns = match ns {
+            Some(ns) => Some(ns),
+            None => {
+                prefix = prefix.or_else(|| self.current_prefix_all.clone());
+                self.current_ns_all.clone()
+            }
+        };
+
+        self.close_start_tag();
+        self.write_prolog()?;
 
         // Write the opening tag immediately: `<tag` (attributes will follow)
-        self.write_element_tag_start(tag, ns.as_deref());
+        self.write_element_tag_start(tag, ns.as_deref(), prefix.as_deref());
         self.collecting_attributes = true;
+        self.may_self_close = self.options.self_close_empty
+            || self.options.void_elements.iter().any(|name| name == tag);
 
         Ok(())
     }
@@ -537,6 +1141,7 @@ impl DomSerializer for XmlSerializer {
         if !self.collecting_attributes {
             return Err(XmlSerializeError {
                 msg: Cow::Borrowed("attribute() called after children_start()"),
+                io: None,
             });
         }
 
@@ -547,18 +1152,21 @@ impl DomSerializer for XmlSerializer {
         };
 
         // Write directly to output
-        self.write_attribute(name, value, ns.as_deref())
-            .map_err(|e| XmlSerializeError {
-                msg: Cow::Owned(format!("write error: {}", e)),
-            })?;
-        Ok(())
+        let prefix = self.pending_prefix.clone();
+        self.write_attribute(name, value, ns.as_deref(), prefix.as_deref())
+            .map_err(XmlSerializeError::io)?;
+        self.flush_if_full()
     }
 
     fn children_start(&mut self) -> Result<(), Self::Error> {
-        // Close the element opening tag
-        self.write_element_tag_end();
+        // Close the element opening tag, unless it may turn out empty
+        if self.may_self_close {
+            self.start_tag_open = true;
+        } else {
+            self.write_element_tag_end();
+        }
         self.collecting_attributes = false;
-        Ok(())
+        self.flush_if_full()
     }
 
     fn children_end(&mut self) -> Result<(), Self::Error> {
@@ -567,14 +1175,37 @@ impl DomSerializer for XmlSerializer {
 
     fn element_end(&mut self, _tag: &str) -> Result<(), Self::Error> {
         if let Some(close_tag) = self.element_stack.pop() {
-            self.write_close_tag(&close_tag);
+            if self.start_tag_open {
+                self.start_tag_open = false;
+                self.out.extend_from_slice(b"/>");
+                self.write_newline();
+            } else {
+                self.write_close_tag(&close_tag);
+            }
         }
-        Ok(())
+        if self.element_stack.is_empty()
+            && let Some(options) = &self.options.canonical
+        {
+            self.out = canonicalize(&self.out, options).map_err(|e| XmlSerializeError {
+                msg: Cow::Owned(format!("canonicalization failed: {e}")),
+                io: None,
+            })?;
+        }
+        self.flush_if_full()
     }
 
     fn text(&mut self, content: &str) -> Result<(), Self::Error> {
-        self.write_text_escaped(content);
-        Ok(())
+        // Empty text leaves the element empty
+        if !content.is_empty() || self.pending_is_cdata {
+            self.close_start_tag();
+        }
+        self.write_prolog()?;
+        if self.pending_is_cdata {
+            self.write_cdata(content);
+        } else {
+            self.write_text_escaped(content);
+        }
+        self.flush_if_full()
     }
 
     fn struct_metadata(&mut self, shape: &facet_core::Shape) -> Result<(), Self::Error> {
@@ -586,8 +1217,18 @@ impl DomSerializer for XmlSerializer {
             .and_then(|attr| attr.get_as::<&str>().copied())
             .map(String::from);
 
-        // If ns_all is set, the next element_start should establish it as default namespace
-        self.pending_establish_default_ns = self.current_ns_all.is_some();
+        // Extract xml::prefix, which puts the struct's namespace on a prefix
+        self.current_prefix_all = shape
+            .attributes
+            .iter()
+            .find(|attr| attr.ns == Some("xml") && attr.key == "prefix")
+            .and_then(|attr| attr.get_as::<&str>().copied())
+            .map(String::from);
+
+        // If ns_all is set without a prefix, the next element_start should
+        // establish it as default namespace
+        self.pending_establish_default_ns =
+            self.current_ns_all.is_some() && self.current_prefix_all.is_none();
 
         Ok(())
     }
@@ -600,6 +1241,11 @@ impl DomSerializer for XmlSerializer {
             self.pending_is_elements = false;
             self.pending_is_doctype = false;
             self.pending_is_tag = false;
+            self.pending_is_cdata = false;
+            self.pending_is_comment = false;
+            self.pending_is_pi = false;
+            self.pending_is_prolog = false;
+            self.pending_prefix = None;
             return Ok(());
         };
 
@@ -613,6 +1259,18 @@ impl DomSerializer for XmlSerializer {
         self.pending_is_doctype = field_def.get_attr(Some("xml"), "doctype").is_some();
         // Check if this field is a tag field
         self.pending_is_tag = field_def.get_attr(Some("xml"), "tag").is_some();
+        // Check if this field's text is written as CDATA
+        self.pending_is_cdata = field_def.get_attr(Some("xml"), "cdata").is_some();
+        // Check if this field collects comments or processing instructions
+        self.pending_is_comment = field_def.get_attr(Some("xml"), "comment").is_some();
+        self.pending_is_pi = field_def.get_attr(Some("xml"), "pi").is_some();
+        self.pending_is_prolog = field_def.get_attr(Some("xml"), "prolog").is_some();
+
+        // Extract xml::prefix attribute from the field
+        self.pending_prefix = field_def
+            .get_attr(Some("xml"), "prefix")
+            .and_then(|attr| attr.get_as::<&str>().copied())
+            .map(String::from);
 
         // Extract xml::ns attribute from the field
         if let Some(ns_attr) = field_def.get_attr(Some("xml"), "ns")
@@ -622,6 +1280,9 @@ impl DomSerializer for XmlSerializer {
         } else if !self.pending_is_attribute && !self.pending_is_text {
             // Apply ns_all to elements only (or None if no ns_all)
             self.pending_namespace = self.current_ns_all.clone();
+            if self.pending_prefix.is_none() {
+                self.pending_prefix = self.current_prefix_all.clone();
+            }
         } else {
             // Attributes and text don't get namespace from ns_all
             self.pending_namespace = None;
@@ -657,7 +1318,51 @@ impl DomSerializer for XmlSerializer {
         self.pending_is_tag
     }
 
+    fn is_comment_field(&self) -> bool {
+        self.pending_is_comment
+    }
+
+    fn is_processing_instruction_field(&self) -> bool {
+        self.pending_is_pi
+    }
+
+    fn is_prolog_field(&self) -> bool {
+        self.pending_is_prolog
+    }
+
+    fn at_document_root(&self) -> bool {
+        self.element_stack.is_empty()
+    }
+
+    fn comment(&mut self, content: &str) -> Result<(), Self::Error> {
+        // `--` may not appear in a comment, and it may not end with `-`
+        if content.contains("--") || content.ends_with('-') {
+            return Err(XmlSerializeError {
+                msg: Cow::Owned(format!(
+                    "comment cannot contain `--` or end with `-`: {content:?}"
+                )),
+                io: None,
+            });
+        }
+        self.close_start_tag();
+        self.write_prolog()?;
+        self.write_indent();
+        self.out.extend_from_slice(b"<!--");
+        self.out.extend_from_slice(content.as_bytes());
+        self.out.extend_from_slice(b"-->");
+        self.write_newline();
+        self.flush_if_full()
+    }
+
+    fn processing_instruction(&mut self, target: &str, data: &str) -> Result<(), Self::Error> {
+        self.close_start_tag();
+        self.write_prolog()?;
+        self.write_processing_instruction(target, data)?;
+        self.flush_if_full()
+    }
+
     fn doctype(&mut self, content: &str) -> Result<(), Self::Error> {
+        self.write_prolog()?;
         // Emit DOCTYPE declaration
         self.out.write_all(b"<!DOCTYPE ").unwrap();
         self.out.write_all(content.as_bytes()).unwrap();
@@ -712,7 +1417,7 @@ where
     T: Facet<'facet> + ?Sized,
 {
     let mut serializer = XmlSerializer::with_options(options.clone());
-    facet_dom::serialize(&mut serializer, Peek::new(value))?;
+    serializer.serialize_value(Peek::new(value))?;
     Ok(serializer.finish())
 }
 
@@ -749,6 +1454,104 @@ where
     Ok(String::from_utf8(bytes).expect("XmlSerializer produces valid UTF-8"))
 }
 
+/// Serialize a value as XML to an [`io::Write`](std::io::Write) sink with default options.
+///
+/// Output is written incrementally through an internal buffer rather than
+/// built in memory first, except with [`SerializeOptions::canonical`], which
+/// needs the whole document before writing it. Errors from the writer are
+/// reported as [`XmlSerializeError`], see [`XmlSerializeError::io_error`].
+///
+/// # Example
+///
+/// ```
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Note {
+///     body: String,
+/// }
+///
+/// let mut out = Vec::new();
+/// facet_xml::to_writer(&mut out, &Note { body: "hi".into() }).unwrap();
+/// assert_eq!(out, b"<note><body>hi</body></note>");
+/// ```
+pub fn to_writer<'facet, W, T>(
+    writer: &mut W,
+    value: &'_ T,
+) -> Result<(), DomSerializeError<XmlSerializeError>>
+where
+    W: Write + ?Sized,
+    T: Facet<'facet> + ?Sized,
+{
+    to_writer_with_options(writer, value, &SerializeOptions::default())
+}
+
+/// Serialize a value as XML to an [`io::Write`](std::io::Write) sink with custom options.
+///
+/// See [`to_writer`]; with [`SerializeOptions::canonical`] the document is
+/// buffered in memory before it is written.
+pub fn to_writer_with_options<'facet, W, T>(
+    writer: &mut W,
+    value: &'_ T,
+    options: &SerializeOptions,
+) -> Result<(), DomSerializeError<XmlSerializeError>>
+where
+    W: Write + ?Sized,
+    T: Facet<'facet> + ?Sized,
+{
+    let mut serializer = XmlSerializer::from_writer_with_options(writer, options.clone());
+    serializer.serialize_value(Peek::new(value))?;
+    serializer
+        .into_inner()
+        .map_err(DomSerializeError::Backend)?;
+    Ok(())
+}
+
+/// Serialize a value as XML to a [`fmt::Write`](core::fmt::Write) sink, like a
+/// `String` or a `Formatter`, with default options.
+pub fn to_fmt_writer<'facet, W, T>(
+    writer: &mut W,
+    value: &'_ T,
+) -> Result<(), DomSerializeError<XmlSerializeError>>
+where
+    W: core::fmt::Write + ?Sized,
+    T: Facet<'facet> + ?Sized,
+{
+    to_fmt_writer_with_options(writer, value, &SerializeOptions::default())
+}
+
+/// Serialize a value as XML to a [`fmt::Write`](core::fmt::Write) sink with custom options.
+pub fn to_fmt_writer_with_options<'facet, W, T>(
+    writer: &mut W,
+    value: &'_ T,
+    options: &SerializeOptions,
+) -> Result<(), DomSerializeError<XmlSerializeError>>
+where
+    W: core::fmt::Write + ?Sized,
+    T: Facet<'facet> + ?Sized,
+{
+    to_writer_with_options(&mut FmtWriter(writer), value, options)
+}
+
+/// Adapts a [`fmt::Write`](core::fmt::Write) sink to [`io::Write`](std::io::Write).
+///
+/// The serializer only flushes whole strings, so every write is valid UTF-8.
+struct FmtWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: core::fmt::Write + ?Sized> Write for FmtWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let s = core::str::from_utf8(buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.0
+            .write_str(s)
+            .map_err(|_| std::io::Error::other("formatter error"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Escape special characters while preserving entity references.
 ///
 /// Recognizes entity reference patterns:
@@ -0,0 +1,188 @@
+//! Tests for field aliases, loose name matching and ambiguous names.
+
+use facet::Facet;
+use facet_xml::{self as xml, DeserializeOptions, NameMatching};
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "user")]
+struct User {
+    #[facet(xml::attribute, xml::alias = "ID", xml::alias = "user-id")]
+    id: u32,
+    #[facet(xml::alias = "UserName", xml::alias = "username")]
+    user_name: String,
+}
+
+#[test]
+fn every_alias_matches() {
+    for input in [
+        r#"<user id="1"><userName>ada</userName></user>"#,
+        r#"<user ID="1"><UserName>ada</UserName></user>"#,
+        r#"<user user-id="1"><username>ada</username></user>"#,
+    ] {
+        let user: User = xml::from_str(input).unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 1,
+                user_name: "ada".into()
+            }
+        );
+    }
+
+    // Other spellings need a looser name matching
+    let input = r#"<user id="1"><user_name>ada</user_name></user>"#;
+    assert!(xml::from_str::<User>(input).is_err());
+}
+
+#[test]
+fn builtin_alias_still_matches() {
+    #[derive(Facet, Debug)]
+    struct Link {
+        #[facet(xml::attribute, alias = "url")]
+        href: String,
+    }
+
+    let link: Link = xml::from_str(r#"<link url="/"/>"#).unwrap();
+    assert_eq!(link.href, "/");
+}
+
+#[test]
+fn ignore_naming_matches_any_convention() {
+    #[derive(Facet, Debug)]
+    struct Account {
+        #[facet(xml::attribute)]
+        account_id: u32,
+        user_name: String,
+    }
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreNaming);
+    for input in [
+        r#"<account accountId="1"><userName>ada</userName></account>"#,
+        r#"<Account AccountID="1"><UserName>ada</UserName></Account>"#,
+        r#"<account account_id="1"><username>ada</username></account>"#,
+        r#"<ACCOUNT account-id="1"><user_name>ada</user_name></ACCOUNT>"#,
+    ] {
+        let account: Account = xml::from_str_with_options(input, &options).unwrap();
+        assert_eq!((account.account_id, account.user_name.as_str()), (1, "ada"));
+    }
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let input = r#"<account accountId="1"><user_name>ada</user_name></account>"#;
+    assert!(xml::from_str_with_options::<Account>(input, &options).is_err());
+}
+
+#[test]
+fn names_that_fold_together_are_ambiguous() {
+    #[derive(Facet, Debug)]
+    struct Profile {
+        #[facet(rename = "user-name", default)]
+        login: String,
+        #[facet(rename = "userName", default)]
+        display: String,
+    }
+
+    let input = "<profile><user-name>a</user-name><userName>b</userName></profile>";
+    let profile: Profile = xml::from_str(input).unwrap();
+    assert_eq!(profile.display, "b");
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    assert!(xml::from_str_with_options::<Profile>(input, &options).is_ok());
+
+    // Reported whatever the input contains
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreNaming);
+    let err = xml::from_str_with_options::<Profile>("<profile/>", &options).unwrap_err();
+    match err.kind() {
+        xml::DeserializeErrorKind::AmbiguousName { names, fields } => {
+            assert_eq!(names, &["user-name", "userName"]);
+            assert_eq!(fields, &["login", "display"]);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert_eq!(
+        err.kind().to_string(),
+        "ambiguous name user-name / userName: matches fields login, display"
+    );
+}
+
+#[test]
+fn alias_clashing_with_another_field_is_ambiguous_when_folding() {
+    #[derive(Facet, Debug)]
+    struct Entry {
+        #[facet(xml::alias = "name", default)]
+        title: String,
+        #[facet(default)]
+        name: String,
+    }
+
+    // Exact matching keeps sending the name to the first field
+    let entry: Entry = xml::from_str("<entry><name>b</name></entry>").unwrap();
+    assert_eq!((entry.title.as_str(), entry.name.as_str()), ("b", ""));
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let err = xml::from_str_with_options::<Entry>("<entry/>", &options).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        xml::DeserializeErrorKind::AmbiguousName { names, .. } if names == &["name"]
+    ));
+}
+
+#[test]
+fn field_named_like_a_singularized_list_is_not_ambiguous() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct R {
+        item: Option<String>,
+        items: Vec<String>,
+    }
+
+    let r: R = xml::from_str("<r><item>a</item></r>").unwrap();
+    assert_eq!(
+        r,
+        R {
+            item: Some("a".into()),
+            items: vec![]
+        }
+    );
+
+    // The explicitly named field wins wherever it is declared
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(rename = "r")]
+    struct Reversed {
+        items: Vec<String>,
+        item: Option<String>,
+    }
+
+    let r: Reversed = xml::from_str("<r><item>a</item></r>").unwrap();
+    assert_eq!(
+        r,
+        Reversed {
+            items: vec![],
+            item: Some("a".into())
+        }
+    );
+}
+
+#[test]
+fn nested_ambiguity_is_located() {
+    #[derive(Facet, Debug)]
+    struct Inner {
+        #[facet(rename = "Id", xml::attribute)]
+        upper: String,
+        #[facet(rename = "id", xml::attribute)]
+        lower: String,
+    }
+
+    #[derive(Facet, Debug)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    let input = r#"<outer><inner Id="a" id="b"/></outer>"#;
+    let outer: Outer = xml::from_str(input).unwrap();
+    assert_eq!(
+        (outer.inner.upper.as_str(), outer.inner.lower.as_str()),
+        ("a", "b")
+    );
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let err = xml::from_str_with_options::<Outer>(input, &options).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "/outer/inner[1]");
+}
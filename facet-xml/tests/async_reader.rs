@@ -0,0 +1,209 @@
+//! Tests for deserializing from tokio `AsyncRead` sources.
+#![cfg(feature = "tokio")]
+
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use facet::Facet;
+use facet_dom::DomEvent;
+use facet_xml::{
+    self as xml, DeserializeErrorKind, DeserializeOptions, ParserLimits, XmlError, XmlParser,
+    from_async_reader, from_async_reader_with_options,
+};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// A reader that hands out at most a few bytes per poll, yielding in between.
+struct Trickle<'a> {
+    data: &'a [u8],
+    chunk: usize,
+    ready: bool,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(data: &'a str, chunk: usize) -> Self {
+        Self {
+            data: data.as_bytes(),
+            chunk,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncRead for Trickle<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        // Alternate between pending and ready to exercise resumption
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        let n = self.chunk.min(buf.remaining()).min(self.data.len());
+        buf.put_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    #[facet(xml::attribute)]
+    id: u32,
+    title: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Feed {
+    #[facet(xml::elements)]
+    entries: Vec<Entry>,
+}
+
+const FEED: &str = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry id="1"><title>R&amp;D</title></entry>
+  <entry id="2"><title><![CDATA[<second>]]></title></entry>
+</feed>"#;
+
+#[tokio::test]
+async fn reads_from_a_trickling_reader() {
+    let feed: Feed = from_async_reader(Trickle::new(FEED, 3)).await.unwrap();
+    assert_eq!(feed, xml::from_str::<Feed>(FEED).unwrap());
+    assert_eq!(feed.entries[1].title, "<second>");
+}
+
+#[tokio::test]
+async fn parser_emits_the_same_events() {
+    let mut parser = XmlParser::from_async_reader(Trickle::new(FEED, 5));
+    let mut events = Vec::new();
+    while let Some(event) = parser.next_event_async().await.unwrap() {
+        events.push(event);
+    }
+
+    let mut sync = XmlParser::new(FEED.as_bytes());
+    let mut expected = Vec::new();
+    while let Some(event) = facet_dom::DomParser::next_event(&mut sync).unwrap() {
+        expected.push(event);
+    }
+    assert_eq!(events, expected);
+    assert!(matches!(events[0], DomEvent::XmlDeclaration { .. }));
+}
+
+#[tokio::test]
+async fn errors_report_paths_and_locations() {
+    let input = "<feed>\n  <entry id=\"1\"><title>t</title></entry>\n  <entry id=\"x\"><title>t</title></entry>\n</feed>";
+    let sync = xml::from_str::<Feed>(input).unwrap_err();
+    let err = from_async_reader::<Feed, _>(Trickle::new(input, 7))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.location(), sync.location());
+    assert_eq!(err.location().map(|l| l.line), Some(3));
+    assert!(err.span().is_some());
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[2]/@id")
+    );
+}
+
+/// A reader that repeats the same piece of input forever, counting the bytes read.
+struct Endless {
+    piece: &'static [u8],
+    offset: usize,
+    read: Arc<AtomicUsize>,
+}
+
+impl AsyncRead for Endless {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let rest = &self.piece[self.offset..];
+        let n = rest.len().min(buf.remaining());
+        buf.put_slice(&rest[..n]);
+        self.offset = (self.offset + n) % self.piece.len();
+        self.read.fetch_add(n, Ordering::Relaxed);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn deserializes_while_reading() {
+    // The document never ends; the deserializer fails on the first entry
+    let read = Arc::new(AtomicUsize::new(0));
+    let reader = tokio::io::AsyncReadExt::chain(
+        &b"<feed><entry id=\"x\"/>"[..],
+        Endless {
+            piece: b"<entry id=\"1\"><title>t</title></entry>",
+            offset: 0,
+            read: read.clone(),
+        },
+    );
+    let err = from_async_reader::<Feed, _>(reader).await.unwrap_err();
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[1]/@id")
+    );
+    assert!(read.load(Ordering::Relaxed) < 1024 * 1024);
+}
+
+#[tokio::test]
+async fn malformed_input_is_reported() {
+    let input = "<feed><entry id=\"1\"><title>t</entry></feed>";
+    let err = from_async_reader::<Feed, _>(input.as_bytes())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        DeserializeErrorKind::Parser(XmlError::Parse(_))
+    ));
+}
+
+#[tokio::test]
+async fn options_and_limits_apply() {
+    let options = DeserializeOptions::new().limits(ParserLimits::new().max_elements(2));
+    let err = from_async_reader_with_options::<Feed, _>(FEED.as_bytes(), &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        DeserializeErrorKind::Parser(XmlError::LimitExceeded { .. })
+    ));
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum_core::body::Body;
+    use axum_core::extract::FromRequest;
+    use facet_xml::Xml;
+
+    use super::*;
+
+    async fn extract(body: Body) -> Result<Feed, facet_xml::XmlRejection> {
+        let request = http::Request::new(body);
+        Xml::<Feed>::from_request(request, &())
+            .await
+            .map(Xml::into_inner)
+    }
+
+    #[tokio::test]
+    async fn extractor_parses_the_body() {
+        let feed = extract(Body::from(FEED)).await.unwrap();
+        assert_eq!(feed.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn extractor_rejects_invalid_documents() {
+        let rejection = extract(Body::from("<feed><entry id='x'/></feed>"))
+            .await
+            .unwrap_err();
+        assert!(rejection.is_deserialize_error());
+    }
+}
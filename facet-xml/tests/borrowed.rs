@@ -0,0 +1,104 @@
+//! Tests for zero-copy deserialization with `from_str_borrowed`.
+
+use std::borrow::Cow;
+
+use facet::Facet;
+use facet_dom::{DomEvent, DomParser};
+use facet_testhelpers::test;
+use facet_xml::{self as xml, XmlParser, from_slice_borrowed, from_str_borrowed};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Book<'a> {
+    #[facet(xml::attribute)]
+    isbn: &'a str,
+    title: &'a str,
+    #[facet(xml::element)]
+    note: Cow<'a, str>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Para<'a> {
+    #[facet(xml::attribute)]
+    lang: Cow<'a, str>,
+    #[facet(xml::text)]
+    body: Cow<'a, str>,
+}
+
+fn assert_within(input: &str, value: &str) {
+    let range = input.as_bytes().as_ptr_range();
+    assert!(
+        range.contains(&value.as_ptr()),
+        "{value:?} was copied instead of borrowed"
+    );
+}
+
+#[test]
+fn str_fields_borrow_from_input() {
+    let input = r#"<book isbn="978-0"><title>  Dune  </title><note>classic</note></book>"#;
+    let book: Book = from_str_borrowed(input).unwrap();
+
+    assert_eq!(book.isbn, "978-0");
+    assert_eq!(book.title, "Dune");
+    assert_within(input, book.isbn);
+    assert_within(input, book.title);
+    assert!(matches!(book.note, Cow::Borrowed("classic")));
+}
+
+#[test]
+fn escaped_values_are_owned() {
+    let input = r#"<para lang="en&amp;fr">Fish &amp; chips</para>"#;
+    let para: Para = from_str_borrowed(input).unwrap();
+
+    assert_eq!(para.lang, "en&fr");
+    assert_eq!(para.body, "Fish & chips");
+    assert!(matches!(para.lang, Cow::Owned(_)));
+    assert!(matches!(para.body, Cow::Owned(_)));
+}
+
+#[test]
+fn text_fields_borrow_from_input() {
+    let input = r#"<para lang="en">Plain text</para>"#;
+    let para: Para = from_str_borrowed(input).unwrap();
+    assert!(matches!(para.lang, Cow::Borrowed("en")));
+    assert!(matches!(para.body, Cow::Borrowed("Plain text")));
+}
+
+#[test]
+fn cdata_borrows_from_input() {
+    let input = "<para lang='en'><![CDATA[a < b]]></para>";
+    let para: Para = from_str_borrowed(input).unwrap();
+    assert!(matches!(para.body, Cow::Borrowed("a < b")));
+}
+
+#[test]
+fn parser_emits_borrowed_events() {
+    let input = br#"<root xmlns:x="urn:x" x:id="7">text</root>"#;
+    let mut parser = XmlParser::new(input);
+
+    let mut borrowed = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        match event {
+            DomEvent::NodeStart { tag, .. } => borrowed.push(matches!(tag, Cow::Borrowed(_))),
+            DomEvent::Attribute { name, value, .. } => {
+                borrowed.push(matches!(name, Cow::Borrowed("id")));
+                borrowed.push(matches!(value, Cow::Borrowed("7")));
+            }
+            DomEvent::Text(text) => borrowed.push(matches!(text, Cow::Borrowed("text"))),
+            _ => {}
+        }
+    }
+    assert_eq!(borrowed, [true, true, true, true]);
+}
+
+#[test]
+fn transcoded_input_cannot_be_borrowed() {
+    #[derive(Facet, Debug)]
+    struct Name<'a> {
+        #[facet(xml::text)]
+        value: Cow<'a, str>,
+    }
+
+    let input = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><name>Ren\xe9</name>";
+    let name: Name = from_slice_borrowed(input).unwrap();
+    assert!(matches!(name.value, Cow::Owned(ref s) if s == "René"));
+}
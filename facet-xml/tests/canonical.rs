@@ -0,0 +1,184 @@
+//! Tests for Canonical XML and Exclusive XML Canonicalization.
+//!
+//! The `c14n/3.*` vectors are the examples of section 3 of the Canonical XML 1.0
+//! recommendation. Examples 3.5 (external entities) and 3.7 and 3.8 (document
+//! subsets) are not included: external entities are never loaded, and only
+//! whole documents are canonicalized. The `c14n/exc-*` vectors are the example
+//! of section 2.2 of the Exclusive XML Canonicalization recommendation,
+//! canonicalized as a whole document.
+
+use facet::Facet;
+use facet_xml::{self as xml, CanonicalOptions, SerializeOptions, canonicalize};
+
+fn check(input: &[u8], options: &CanonicalOptions, expected: &[u8]) {
+    let out = canonicalize(input, options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap()
+    );
+}
+
+#[test]
+fn w3c_pis_comments_and_outside_of_document_element() {
+    let input = include_bytes!("c14n/3.1-input.xml");
+    check(
+        input,
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/3.1-c14n.xml"),
+    );
+    check(
+        input,
+        &CanonicalOptions::new().with_comments(true),
+        include_bytes!("c14n/3.1-c14n-comments.xml"),
+    );
+}
+
+#[test]
+fn w3c_whitespace_in_document_content() {
+    check(
+        include_bytes!("c14n/3.2-input.xml"),
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/3.2-c14n.xml"),
+    );
+}
+
+#[test]
+fn w3c_start_and_end_tags() {
+    check(
+        include_bytes!("c14n/3.3-input.xml"),
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/3.3-c14n.xml"),
+    );
+}
+
+#[test]
+fn w3c_character_modifications_and_references() {
+    check(
+        include_bytes!("c14n/3.4-input.xml"),
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/3.4-c14n.xml"),
+    );
+}
+
+#[test]
+fn w3c_utf8_encoding() {
+    check(
+        include_bytes!("c14n/3.6-input.xml"),
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/3.6-c14n.xml"),
+    );
+
+    // The same character as a literal ISO-8859-1 byte
+    let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<doc>\xA9</doc>";
+    check(latin1, &CanonicalOptions::new(), "<doc>©</doc>".as_bytes());
+}
+
+#[test]
+fn w3c_exclusive_namespaces() {
+    let input = include_bytes!("c14n/exc-input.xml");
+    check(
+        input,
+        &CanonicalOptions::new(),
+        include_bytes!("c14n/exc-c14n.xml"),
+    );
+    check(
+        input,
+        &CanonicalOptions::new().exclusive(true),
+        include_bytes!("c14n/exc-exc-c14n.xml"),
+    );
+    // Prefixes in the inclusive list are declared where they come into scope
+    check(
+        input,
+        &CanonicalOptions::new()
+            .exclusive(true)
+            .inclusive_prefixes(["n3"]),
+        include_bytes!("c14n/exc-c14n.xml"),
+    );
+}
+
+#[test]
+fn exclusive_default_namespace() {
+    let input = br#"<a xmlns="urn:a" xmlns:unused="urn:u"><b xmlns=""><c xmlns="urn:a"/></b></a>"#;
+    check(
+        input,
+        &CanonicalOptions::new().exclusive(true),
+        br#"<a xmlns="urn:a"><b xmlns=""><c xmlns="urn:a"></c></b></a>"#,
+    );
+}
+
+#[test]
+fn unbound_prefix_is_an_error() {
+    assert!(canonicalize(b"<p:a/>", &CanonicalOptions::new().exclusive(true)).is_err());
+    assert!(canonicalize(b"<a p:b=\"1\"/>", &CanonicalOptions::new()).is_err());
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(xml::ns_all = "urn:orders")]
+struct Order {
+    #[facet(xml::attribute)]
+    status: String,
+    #[facet(xml::attribute)]
+    id: u32,
+    #[facet(xml::attribute, xml::ns = "http://www.w3.org/XML/1998/namespace")]
+    lang: String,
+    note: String,
+}
+
+fn order() -> Order {
+    Order {
+        status: "open".into(),
+        id: 7,
+        lang: "en".into(),
+        note: "line one\r\nline\ttwo".into(),
+    }
+}
+
+#[test]
+fn serializer_writes_canonical_form() {
+    let options = SerializeOptions::new()
+        .declaration(xml::XmlDeclaration::new())
+        .pretty()
+        .canonical(CanonicalOptions::new());
+    let out = xml::to_string_with_options(&order(), &options).unwrap();
+    assert_eq!(
+        out,
+        "<order xmlns=\"urn:orders\" id=\"7\" status=\"open\" xml:lang=\"en\"><note>line one&#xD;\nline\ttwo</note></order>"
+    );
+
+    // Canonicalizing the canonical form changes nothing
+    let again = canonicalize(out.as_bytes(), &CanonicalOptions::new()).unwrap();
+    assert_eq!(again, out.as_bytes());
+}
+
+#[test]
+fn serializer_canonical_form_matches_document_canonicalization() {
+    let order = Order {
+        note: "a < b & c".into(),
+        ..order()
+    };
+    let plain = xml::to_vec(&order).unwrap();
+    let options = CanonicalOptions::new().exclusive(true);
+    let mut written = Vec::new();
+    xml::to_writer_with_options(
+        &mut written,
+        &order,
+        &SerializeOptions::new().canonical(options.clone()),
+    )
+    .unwrap();
+    assert_eq!(written, canonicalize(&plain, &options).unwrap());
+}
+
+#[test]
+fn entities_expanding_to_markup_are_rejected() {
+    let input = br#"<!DOCTYPE d [<!ENTITY e "<b>x</b>">]><d>&e;</d>"#;
+    let err = canonicalize(input, &CanonicalOptions::new()).unwrap_err();
+    assert!(err.to_string().contains("entity `e`"), "got: {err}");
+
+    // Also through another entity
+    let input = br#"<!DOCTYPE d [<!ENTITY e "<b>x</b>"><!ENTITY f "a &e;">]><d>&f;</d>"#;
+    assert!(canonicalize(input, &CanonicalOptions::new()).is_err());
+
+    // Escaped markup is character data
+    let input = br#"<!DOCTYPE d [<!ENTITY e "a &lt;b&gt; c">]><d>&e;</d>"#;
+    check(input, &CanonicalOptions::new(), b"<d>a &lt;b&gt; c</d>");
+}
@@ -0,0 +1,69 @@
+//! Tests for serializing text as CDATA with xml::cdata.
+
+use facet::Facet;
+use facet_xml as xml;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    title: String,
+    #[facet(xml::cdata)]
+    content: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Script {
+    #[facet(xml::attribute)]
+    lang: String,
+    #[facet(xml::text, xml::cdata)]
+    body: String,
+}
+
+#[test]
+fn element_field_is_wrapped() {
+    let entry = Entry {
+        title: "a < b".into(),
+        content: "<p>Hello &amp; welcome</p>".into(),
+    };
+
+    let out = xml::to_string(&entry).unwrap();
+    assert_eq!(
+        out,
+        "<entry><title>a &lt; b</title><content><![CDATA[<p>Hello &amp; welcome</p>]]></content></entry>"
+    );
+    assert_eq!(xml::from_str::<Entry>(&out).unwrap(), entry);
+}
+
+#[test]
+fn text_field_is_wrapped() {
+    let script = Script {
+        lang: "js".into(),
+        body: "if (a < b && c) { go(); }".into(),
+    };
+
+    let out = xml::to_string(&script).unwrap();
+    assert_eq!(
+        out,
+        r#"<script lang="js"><![CDATA[if (a < b && c) { go(); }]]></script>"#
+    );
+    assert_eq!(xml::from_str::<Script>(&out).unwrap(), script);
+}
+
+#[test]
+fn end_marker_is_split() {
+    let entry = Entry {
+        title: "t".into(),
+        content: "a]]>b]]>".into(),
+    };
+
+    let out = xml::to_string(&entry).unwrap();
+    assert!(out.contains("<content><![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]></content>"));
+    assert_eq!(xml::from_str::<Entry>(&out).unwrap(), entry);
+}
+
+#[test]
+fn escaped_text_still_parses() {
+    let entry: Entry =
+        xml::from_str("<entry><title>t</title><content>&lt;p&gt;hi&lt;/p&gt;</content></entry>")
+            .unwrap();
+    assert_eq!(entry.content, "<p>hi</p>");
+}
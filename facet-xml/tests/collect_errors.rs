@@ -0,0 +1,138 @@
+//! Tests for reporting every recoverable error of a document at once.
+
+use facet::Facet;
+use facet_xml as xml;
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(deny_unknown_fields)]
+struct Entry {
+    #[facet(xml::attribute)]
+    id: u32,
+    title: String,
+    count: u32,
+    #[facet(default)]
+    note: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(deny_unknown_fields)]
+struct Feed {
+    #[facet(xml::elements)]
+    entries: Vec<Entry>,
+}
+
+fn paths(errors: &[xml::DeserializeError<xml::XmlError>]) -> Vec<String> {
+    errors
+        .iter()
+        .map(|e| e.path().map(ToString::to_string).unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn valid_document_deserializes() {
+    let input = r#"<feed><entry id="1"><title>a</title><count>2</count></entry></feed>"#;
+    let feed: Feed = xml::from_str_collecting(input).unwrap();
+    assert_eq!(feed, xml::from_str::<Feed>(input).unwrap());
+}
+
+#[test]
+fn reports_every_recoverable_error() {
+    let input = r#"<feed>
+  <entry id="x" lang="en"><title>a</title><count>many</count></entry>
+  <entry id="2"><count>3</count><bogus/></entry>
+  <entry id="3"><title>c</title><count>-1</count></entry>
+</feed>"#;
+    let errors = xml::from_str_collecting::<Feed>(input).unwrap_err();
+
+    assert_eq!(
+        paths(&errors),
+        [
+            "/feed/entry[1]/@id",
+            "/feed/entry[1]/@lang",
+            "/feed/entry[1]/count[1]",
+            "/feed/entry[2]",
+            "/feed/entry[2]",
+            "/feed/entry[3]/count[1]",
+        ]
+    );
+    assert!(matches!(
+        errors[1].kind(),
+        xml::DeserializeErrorKind::UnknownAttribute { name } if name == "lang"
+    ));
+    assert!(matches!(
+        errors[3].kind(),
+        xml::DeserializeErrorKind::UnknownElement { tag } if tag == "bogus"
+    ));
+    assert!(matches!(
+        errors[4].kind(),
+        xml::DeserializeErrorKind::MissingElement { tag } if tag == "title"
+    ));
+
+    // Parse errors point at the offending text
+    let span = errors[2].span().unwrap();
+    assert_eq!(&input[span.offset as usize..span.end()], "many");
+    let location = errors[5].location().unwrap();
+    assert_eq!((location.line, location.column), (4, 40));
+}
+
+#[test]
+fn missing_attribute_is_reported_by_name() {
+    #[derive(Facet, Debug)]
+    struct Link {
+        #[facet(xml::attribute)]
+        href: String,
+        #[facet(xml::attribute, rename = "rel")]
+        relation: String,
+    }
+
+    let errors = xml::from_str_collecting::<Link>("<link/>").unwrap_err();
+    let names: Vec<_> = errors
+        .iter()
+        .map(|e| match e.kind() {
+            xml::DeserializeErrorKind::MissingAttribute { name } => name.as_str(),
+            other => panic!("unexpected error: {other:?}"),
+        })
+        .collect();
+    assert_eq!(names, ["href", "rel"]);
+}
+
+#[test]
+fn unrecoverable_error_is_reported_last() {
+    let input = r#"<feed><entry id="x"><title>a</title><count>1</count></entry><entry"#;
+    let errors = xml::from_str_collecting::<Feed>(input).unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(paths(&errors[..1]), ["/feed/entry[1]/@id"]);
+    assert!(matches!(
+        errors[1].kind(),
+        xml::DeserializeErrorKind::Parser(_)
+    ));
+}
+
+#[test]
+fn missing_field_without_default_ends_deserialization() {
+    #[derive(Facet, Debug)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Facet, Debug)]
+    struct Person {
+        age: u32,
+        address: Address,
+    }
+
+    let errors = xml::from_str_collecting::<Person>("<person><age>x</age></person>").unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[1].kind(),
+        xml::DeserializeErrorKind::MissingElement { tag } if tag == "address"
+    ));
+}
+
+#[test]
+fn fail_fast_is_unchanged() {
+    let input = r#"<feed><entry id="x"><title>a</title><count>many</count></entry></feed>"#;
+    let err = xml::from_str::<Feed>(input).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "/feed/entry[1]/@id");
+}
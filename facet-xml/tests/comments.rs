@@ -0,0 +1,200 @@
+//! Tests for capturing comments and processing instructions with xml::comment and xml::pi.
+
+use facet::Facet;
+use facet_xml as xml;
+
+#[derive(Facet, Debug, PartialEq)]
+struct Stylesheet {
+    target: String,
+    data: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Config {
+    #[facet(xml::comment, xml::prolog)]
+    comments: Vec<String>,
+    #[facet(xml::pi, xml::prolog)]
+    instructions: Vec<Stylesheet>,
+    name: String,
+}
+
+#[test]
+fn collects_prolog_and_child_comments_separately() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        #[facet(xml::comment, xml::prolog)]
+        header: Vec<String>,
+        #[facet(xml::pi, xml::prolog)]
+        instructions: Vec<Stylesheet>,
+        #[facet(xml::comment)]
+        notes: Vec<String>,
+        name: String,
+    }
+
+    let input = r#"<?xml version="1.0"?>
+<!-- managed-by: ops -->
+<?xml-stylesheet type="text/xsl" href="style.xsl"?>
+<config><!-- do not edit --><name>app</name></config>"#;
+
+    let config: Config = xml::from_str(input).unwrap();
+    assert_eq!(config.header, [" managed-by: ops "]);
+    assert_eq!(config.notes, [" do not edit "]);
+    assert_eq!(
+        config.instructions,
+        [Stylesheet {
+            target: "xml-stylesheet".into(),
+            data: r#"type="text/xsl" href="style.xsl""#.into(),
+        }]
+    );
+    assert_eq!(config.name, "app");
+}
+
+#[test]
+fn comments_and_pis_are_optional() {
+    let config: Config = xml::from_str("<config><name>app</name></config>").unwrap();
+    assert!(config.comments.is_empty());
+    assert!(config.instructions.is_empty());
+}
+
+#[test]
+fn skipped_without_a_field() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Plain {
+        name: String,
+    }
+
+    let input = "<!-- c --><?app run?><plain><?app run?><name>app</name></plain>";
+    let plain: Plain = xml::from_str(input).unwrap();
+    assert_eq!(plain.name, "app");
+}
+
+#[test]
+fn string_items_hold_target_and_data() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Doc {
+        #[facet(xml::pi)]
+        pis: Vec<String>,
+        #[facet(xml::comment)]
+        last_comment: Option<String>,
+    }
+
+    let doc: Doc = xml::from_str("<?page break?><doc><!--a--><!--b--><?flush?></doc>").unwrap();
+    assert_eq!(doc.pis, ["flush"]);
+    assert_eq!(doc.last_comment.as_deref(), Some("b"));
+
+    let out = xml::to_string(&doc).unwrap();
+    assert_eq!(out, "<doc><?flush?><!--b--></doc>");
+}
+
+#[test]
+fn nested_comments_stay_in_place() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        #[facet(xml::comment)]
+        notes: Vec<String>,
+        port: u16,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Settings {
+        server: Server,
+    }
+
+    let settings = Settings {
+        server: Server {
+            host: "localhost".into(),
+            notes: vec![" default port ".into()],
+            port: 8080,
+        },
+    };
+    let out = xml::to_string(&settings).unwrap();
+    assert_eq!(
+        out,
+        "<settings><server><host>localhost</host><!-- default port --><port>8080</port></server></settings>"
+    );
+
+    let parsed: Settings = xml::from_str(&out).unwrap();
+    assert_eq!(parsed, settings);
+}
+
+#[test]
+fn roundtrip_in_prolog() {
+    let config = Config {
+        comments: vec![" managed-by: ops ".into()],
+        instructions: vec![Stylesheet {
+            target: "xml-stylesheet".into(),
+            data: r#"href="style.css""#.into(),
+        }],
+        name: "app".into(),
+    };
+
+    let out = xml::to_string(&config).unwrap();
+    assert_eq!(
+        out,
+        r#"<!-- managed-by: ops --><?xml-stylesheet href="style.css"?><config><name>app</name></config>"#
+    );
+    assert_eq!(xml::from_str::<Config>(&out).unwrap(), config);
+
+    let pretty = xml::to_string_pretty(&config).unwrap();
+    assert_eq!(xml::from_str::<Config>(&pretty).unwrap(), config);
+}
+
+#[test]
+fn root_child_comments_stay_inside_the_root() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        #[facet(xml::comment)]
+        comments: Vec<String>,
+        a: String,
+    }
+
+    let input = "<config><!-- managed-by: ops --><a>x</a></config>";
+    let config: Config = xml::from_str(input).unwrap();
+    assert_eq!(config.comments, [" managed-by: ops "]);
+    assert_eq!(xml::to_string(&config).unwrap(), input);
+
+    // Without a prolog field, comments before the root are dropped
+    let config: Config = xml::from_str("<!-- header --><config><a>x</a></config>").unwrap();
+    assert!(config.comments.is_empty());
+}
+
+#[test]
+fn comments_are_written_at_the_field_position() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        #[facet(xml::comment)]
+        comments: Vec<String>,
+        a: String,
+        b: String,
+    }
+
+    // Positions among the children are not recorded
+    let input = "<config><a>x</a><!-- after a --><b>y</b></config>";
+    let config: Config = xml::from_str(input).unwrap();
+    assert_eq!(config.comments, [" after a "]);
+    assert_eq!(
+        xml::to_string(&config).unwrap(),
+        "<config><!-- after a --><a>x</a><b>y</b></config>"
+    );
+}
+
+#[test]
+fn rejects_invalid_markup() {
+    let config = Config {
+        comments: vec!["a -- b".into()],
+        instructions: vec![],
+        name: "app".into(),
+    };
+    assert!(xml::to_string(&config).is_err());
+
+    let config = Config {
+        comments: vec![],
+        instructions: vec![Stylesheet {
+            target: "app".into(),
+            data: "?>".into(),
+        }],
+        name: "app".into(),
+    };
+    assert!(xml::to_string(&config).is_err());
+}
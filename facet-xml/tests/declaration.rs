@@ -0,0 +1,80 @@
+//! Tests for the XML declaration and prolog processing instructions in SerializeOptions.
+
+use facet::Facet;
+use facet_xml::{self as xml, SerializeOptions, XmlDeclaration};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Feed {
+    title: String,
+}
+
+fn feed() -> Feed {
+    Feed {
+        title: "News".into(),
+    }
+}
+
+#[test]
+fn no_declaration_by_default() {
+    let out = xml::to_string(&feed()).unwrap();
+    assert_eq!(out, "<feed><title>News</title></feed>");
+}
+
+#[test]
+fn default_declaration() {
+    let options = SerializeOptions::new().declaration(XmlDeclaration::new());
+    let out = xml::to_string_with_options(&feed(), &options).unwrap();
+    assert_eq!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8"?><feed><title>News</title></feed>"#
+    );
+    assert_eq!(xml::from_str::<Feed>(&out).unwrap(), feed());
+}
+
+#[test]
+fn custom_declaration() {
+    let declaration = XmlDeclaration::new()
+        .version("1.1")
+        .encoding(None::<&str>)
+        .standalone(false);
+    let options = SerializeOptions::new().declaration(declaration);
+    let out = xml::to_string_with_options(&feed(), &options).unwrap();
+    assert!(out.starts_with(r#"<?xml version="1.1" standalone="no"?><feed>"#));
+}
+
+#[test]
+fn declaration_pis_and_doctype_in_order() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Page {
+        #[facet(xml::doctype)]
+        doctype: Option<String>,
+        body: String,
+    }
+
+    let page = Page {
+        doctype: Some("page SYSTEM \"page.dtd\"".into()),
+        body: "text".into(),
+    };
+    let options = SerializeOptions::new()
+        .declaration(XmlDeclaration::new())
+        .processing_instruction("xml-stylesheet", r#"type="text/xsl" href="page.xsl""#)
+        .pretty();
+    let out = xml::to_string_with_options(&page, &options).unwrap();
+    assert!(out.starts_with(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet type="text/xsl" href="page.xsl"?>
+<!DOCTYPE page SYSTEM "page.dtd">
+<page>
+"#
+    ));
+    assert_eq!(xml::from_str::<Page>(&out).unwrap(), page);
+}
+
+#[test]
+fn invalid_processing_instruction_is_an_error() {
+    let options = SerializeOptions::new().processing_instruction("xml", "version=\"1.0\"");
+    assert!(xml::to_string_with_options(&feed(), &options).is_err());
+
+    let options = SerializeOptions::new().processing_instruction("app", "a ?> b");
+    assert!(xml::to_string_with_options(&feed(), &options).is_err());
+}
@@ -0,0 +1,136 @@
+//! Tests for the matching options of `DeserializeOptions`.
+
+use facet::Facet;
+use facet_xml::{self as xml, DeserializeOptions, NameMatching, NamespaceMatching};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Book {
+    #[facet(xml::attribute)]
+    id: u32,
+    title: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+enum Inline {
+    Bold(String),
+    Italic(String),
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Para {
+    #[facet(flatten)]
+    content: Vec<Inline>,
+}
+
+#[test]
+fn lenient_drops_text_without_a_field() {
+    let input = "<para>Some <bold>loud</bold> and <italic>slanted</italic> text</para>";
+    assert!(xml::from_str::<Para>(input).is_err());
+
+    let options = DeserializeOptions::new().lenient(true);
+    let para: Para = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(
+        para.content,
+        [
+            Inline::Bold("loud".into()),
+            Inline::Italic("slanted".into())
+        ]
+    );
+}
+
+#[test]
+fn ignore_case() {
+    let input = r#"<BOOK id="7"><Title>Dune</Title></BOOK>"#;
+    assert!(xml::from_str::<Book>(input).is_err());
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let book: Book = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(
+        book,
+        Book {
+            id: 7,
+            title: "Dune".into()
+        }
+    );
+
+    let input = r#"<book ID="7"><title>Dune</title></book>"#;
+    let book: Book = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(book.id, 7);
+}
+
+#[test]
+fn ignore_case_in_enum_variants() {
+    let input = "<para><Bold>loud</Bold><ITALIC>slanted</ITALIC></para>";
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let para: Para = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(
+        para.content,
+        [
+            Inline::Bold("loud".into()),
+            Inline::Italic("slanted".into())
+        ]
+    );
+}
+
+#[test]
+fn root_name_override() {
+    let input = r#"<volume id="1"><title>Emma</title></volume>"#;
+    assert!(xml::from_str::<Book>(input).is_err());
+
+    let options = DeserializeOptions::new().root_name("volume");
+    let book: Book = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(book.title, "Emma");
+
+    // The derived name no longer matches
+    let input = r#"<book id="1"><title>Emma</title></book>"#;
+    assert!(xml::from_str_with_options::<Book>(input, &options).is_err());
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    #[facet(xml::ns = "urn:dc")]
+    creator: Option<String>,
+    title: Option<String>,
+}
+
+#[test]
+fn namespace_matching_if_declared() {
+    let input = r#"<entry xmlns:dc="urn:dc" xmlns:x="urn:x"><x:creator>A</x:creator><x:title>T</x:title></entry>"#;
+    let entry: Entry = xml::from_str(input).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            creator: None,
+            title: Some("T".into())
+        }
+    );
+}
+
+#[test]
+fn namespace_matching_ignore() {
+    let input = r#"<entry xmlns:x="urn:x"><x:creator>A</x:creator><title>T</title></entry>"#;
+    let options = DeserializeOptions::new().namespace_matching(NamespaceMatching::Ignore);
+    let entry: Entry = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            creator: Some("A".into()),
+            title: Some("T".into())
+        }
+    );
+}
+
+#[test]
+fn namespace_matching_exact() {
+    let input = r#"<entry xmlns:dc="urn:dc" xmlns:x="urn:x"><dc:creator>A</dc:creator><title>U</title><x:title>T</x:title></entry>"#;
+    let options = DeserializeOptions::new().namespace_matching(NamespaceMatching::Exact);
+    let entry: Entry = xml::from_str_with_options(input, &options).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            creator: Some("A".into()),
+            title: Some("U".into())
+        }
+    );
+}
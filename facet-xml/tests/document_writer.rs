@@ -0,0 +1,95 @@
+//! Tests for writing documents item by item with XmlDocumentWriter.
+
+use facet::Facet;
+use facet_xml::{self as xml, RootElement, SerializeOptions, XmlDeclaration, XmlDocumentWriter};
+
+const SITEMAP_NS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+const IMAGE_NS: &str = "http://www.google.com/schemas/sitemap-image/1.1";
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(
+    rename = "url",
+    xml::ns_all = "http://www.sitemaps.org/schemas/sitemap/0.9"
+)]
+struct Url {
+    loc: String,
+    #[facet(xml::ns = "http://www.google.com/schemas/sitemap-image/1.1")]
+    image: Option<String>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(
+    rename = "urlset",
+    xml::ns_all = "http://www.sitemaps.org/schemas/sitemap/0.9"
+)]
+struct UrlSet {
+    #[facet(xml::elements)]
+    urls: Vec<Url>,
+}
+
+fn url(i: usize) -> Url {
+    Url {
+        loc: format!("https://example.com/{i}"),
+        image: i
+            .is_multiple_of(2)
+            .then(|| format!("https://example.com/{i}.png")),
+    }
+}
+
+fn write_sitemap(len: usize, options: SerializeOptions) -> String {
+    let root = RootElement::new("urlset")
+        .default_namespace(SITEMAP_NS)
+        .namespace("image", IMAGE_NS);
+    let mut doc = XmlDocumentWriter::open_with_options(Vec::new(), &root, options).unwrap();
+    for i in 0..len {
+        doc.write_item(&url(i)).unwrap();
+    }
+    String::from_utf8(doc.close().unwrap()).unwrap()
+}
+
+#[test]
+fn root_namespaces_are_reused() {
+    let out = write_sitemap(2, SerializeOptions::new());
+    assert_eq!(
+        out,
+        format!(
+            r#"<urlset xmlns="{SITEMAP_NS}" xmlns:image="{IMAGE_NS}"><url><loc>https://example.com/0</loc><image:image>https://example.com/0.png</image:image></url><url><loc>https://example.com/1</loc></url></urlset>"#
+        )
+    );
+}
+
+#[test]
+fn output_parses_back() {
+    let out = write_sitemap(1_000, SerializeOptions::new().pretty());
+    let set: UrlSet = xml::from_str(&out).unwrap();
+    assert_eq!(set.urls.len(), 1_000);
+    assert_eq!(set.urls[998], url(998));
+    assert_eq!(set.urls[999], url(999));
+}
+
+#[test]
+fn declaration_and_root_attributes() {
+    let root = RootElement::new("feed").attribute("note", "a \"b\" & c");
+    let options = SerializeOptions::new().declaration(XmlDeclaration::new());
+    let doc = XmlDocumentWriter::open_with_options(Vec::new(), &root, options).unwrap();
+    let out = String::from_utf8(doc.close().unwrap()).unwrap();
+    assert_eq!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8"?><feed note="a &quot;b&quot; &amp; c"></feed>"#
+    );
+}
+
+#[test]
+fn item_errors_are_reported() {
+    #[derive(Facet)]
+    struct Note {
+        #[facet(xml::comment)]
+        comments: Vec<String>,
+    }
+
+    let mut doc = XmlDocumentWriter::open(Vec::new(), &RootElement::new("notes")).unwrap();
+    let bad = Note {
+        comments: vec!["--".into()],
+    };
+    assert!(doc.write_item(&bad).is_err());
+}
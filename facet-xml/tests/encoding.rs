@@ -0,0 +1,155 @@
+//! Tests for detecting and transcoding non-UTF-8 input.
+
+use facet::Facet;
+use facet_dom::DomParser;
+use facet_testhelpers::test;
+use facet_xml::{
+    self as xml, DeserializeErrorKind, Encoding, RawMarkup, XmlError, XmlParser, detect_encoding,
+    from_slice,
+};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Account {
+    #[facet(xml::attribute)]
+    currency: String,
+    holder: String,
+}
+
+fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+    let mut bytes = if little_endian {
+        vec![0xFF, 0xFE]
+    } else {
+        vec![0xFE, 0xFF]
+    };
+    for unit in text.encode_utf16() {
+        bytes.extend(if little_endian {
+            unit.to_le_bytes()
+        } else {
+            unit.to_be_bytes()
+        });
+    }
+    bytes
+}
+
+#[test]
+fn latin1_declaration_is_transcoded() {
+    let input =
+        b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<account currency=\"\xa3\"><holder>Ren\xe9e M\xfcller</holder></account>";
+    assert_eq!(detect_encoding(input).unwrap(), Encoding::Latin1);
+
+    let account: Account = from_slice(input).unwrap();
+    assert_eq!(account.currency, "£");
+    assert_eq!(account.holder, "Renée Müller");
+}
+
+#[test]
+fn windows_1252_maps_the_c1_range() {
+    let input = b"<?xml version='1.0' encoding='windows-1252'?><account currency='\x80'><holder>\x93Quoted\x94</holder></account>";
+    let account: Account = from_slice(input).unwrap();
+    assert_eq!(account.currency, "€");
+    assert_eq!(account.holder, "\u{201C}Quoted\u{201D}");
+}
+
+#[test]
+fn utf16_with_bom_is_transcoded() {
+    let text = r#"<?xml version="1.0" encoding="UTF-16"?><account currency="¥"><holder>山田 🌸</holder></account>"#;
+    for little_endian in [true, false] {
+        let input = utf16(text, little_endian);
+        let expected = if little_endian {
+            Encoding::Utf16Le
+        } else {
+            Encoding::Utf16Be
+        };
+        assert_eq!(detect_encoding(&input).unwrap(), expected);
+
+        let account: Account = from_slice(&input).unwrap();
+        assert_eq!(account.currency, "¥");
+        assert_eq!(account.holder, "山田 🌸");
+    }
+}
+
+#[test]
+fn utf8_bom_is_skipped() {
+    let input = b"\xEF\xBB\xBF<account currency=\"EUR\"><holder>A</holder></account>";
+    assert_eq!(detect_encoding(input).unwrap(), Encoding::Utf8);
+    let account: Account = from_slice(input).unwrap();
+    assert_eq!(account.currency, "EUR");
+}
+
+#[test]
+fn parser_reports_the_detected_encoding() {
+    let input = utf16("<holder>x</holder>", true);
+    let parser = XmlParser::new(&input);
+    assert_eq!(parser.encoding(), Encoding::Utf16Le);
+
+    assert_eq!(XmlParser::new(b"<a/>").encoding(), Encoding::Utf8);
+}
+
+#[test]
+fn unsupported_encoding_is_an_error() {
+    let input = b"<?xml version=\"1.0\" encoding=\"EBCDIC-US\"?><a/>";
+    assert!(matches!(
+        detect_encoding(input),
+        Err(XmlError::UnsupportedEncoding(label)) if label == "EBCDIC-US"
+    ));
+
+    let mut parser = XmlParser::new(input);
+    assert!(matches!(
+        parser.next_event(),
+        Err(XmlError::UnsupportedEncoding(_))
+    ));
+
+    let err = from_slice::<Account>(input).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        DeserializeErrorKind::Parser(XmlError::UnsupportedEncoding(_))
+    ));
+}
+
+#[test]
+fn utf16_declaration_on_8bit_input_is_an_error() {
+    for label in ["UTF-16", "UTF-16LE", "utf-16be"] {
+        let input = format!("<?xml version=\"1.0\" encoding=\"{label}\"?><a/>");
+        assert!(matches!(
+            detect_encoding(input.as_bytes()),
+            Err(XmlError::UnsupportedEncoding(found)) if found == label
+        ));
+    }
+    assert_eq!(Encoding::from_label("utf-16"), None);
+}
+
+#[test]
+fn transcoded_input_supports_raw_capture_and_locations() {
+    #[derive(Facet, Debug)]
+    struct Doc {
+        body: RawMarkup,
+        count: u32,
+    }
+
+    let input = b"<?xml version=\"1.0\" encoding=\"latin1\"?>\n<doc>\n<body><p>caf\xe9</p></body>\n<count>x</count></doc>";
+    let err = from_slice::<Doc>(input).unwrap_err();
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((4, 8)));
+
+    let fixed = b"<?xml version=\"1.0\" encoding=\"latin1\"?><doc><body><p>caf\xe9</p></body><count>1</count></doc>";
+    let doc: Doc = from_slice(fixed).unwrap();
+    assert_eq!(doc.body.as_str(), "<body><p>café</p></body>");
+}
+
+#[test]
+fn spans_index_the_input_with_its_byte_order_mark() {
+    #[derive(Facet, Debug)]
+    struct Doc {
+        count: u32,
+    }
+
+    let plain = "<doc><count>x</count></doc>";
+    let with_bom = format!("\u{FEFF}{plain}");
+    let without = from_slice::<Doc>(plain.as_bytes()).unwrap_err();
+    let err = from_slice::<Doc>(with_bom.as_bytes()).unwrap_err();
+
+    // The span is shifted past the byte order mark, the column is not
+    let span = err.span().unwrap();
+    assert_eq!(span.offset, without.span().unwrap().offset + 3);
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((1, 13)));
+    assert_eq!(err.location(), without.location());
+}
@@ -0,0 +1,122 @@
+//! Tests for entity declarations in the DTD internal subset.
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{
+    self as xml, DeserializeError, DeserializeErrorKind, DeserializeOptions, LimitKind, XmlError,
+    from_str, from_str_with_options,
+};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Doc {
+    #[facet(xml::attribute, default)]
+    href: String,
+    #[facet(xml::text, default)]
+    text: String,
+}
+
+fn parser_error(err: &DeserializeError<XmlError>) -> &XmlError {
+    match err.kind() {
+        DeserializeErrorKind::Parser(e) => e,
+        other => panic!("expected a parser error, got {other:?}"),
+    }
+}
+
+#[test]
+fn declared_entities_expand_in_text_and_attributes() {
+    let input = r#"<!DOCTYPE doc [
+  <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+  <!ENTITY product 'Widget &amp; Co'>
+]>
+<doc href="&ns_svg;#root">Made by &product;.</doc>"#;
+    let doc: Doc = from_str(input).unwrap();
+
+    assert_eq!(doc.href, "http://www.w3.org/2000/svg#root");
+    assert_eq!(doc.text, "Made by Widget & Co.");
+}
+
+#[test]
+fn nested_entities_expand() {
+    let input = r#"<!DOCTYPE doc [
+  <!ENTITY first "Ada">
+  <!ENTITY full "&first; Lovelace &#x2764;">
+]>
+<doc>&full;</doc>"#;
+    let doc: Doc = from_str(input).unwrap();
+    assert_eq!(doc.text, "Ada Lovelace \u{2764}");
+}
+
+#[test]
+fn first_declaration_wins_and_others_are_ignored() {
+    let input = r#"<!DOCTYPE doc PUBLIC "-//X//DTD X//EN" "x.dtd" [
+  <!-- <!ENTITY name "commented out"> -->
+  <!ENTITY % param "ignored">
+  <!ENTITY ext SYSTEM "ext.xml">
+  <!ELEMENT doc (#PCDATA)>
+  <!ENTITY name "first">
+  <!ENTITY name "second">
+]>
+<doc>&name; &ext;</doc>"#;
+    let doc: Doc = from_str(input).unwrap();
+    assert_eq!(doc.text, "first &ext;");
+}
+
+#[test]
+fn unknown_entities_are_kept_by_default() {
+    let doc: Doc = from_str("<doc>&nbsp;</doc>").unwrap();
+    assert_eq!(doc.text, "&nbsp;");
+}
+
+#[test]
+fn unknown_entities_can_be_an_error() {
+    let options = DeserializeOptions::new().deny_unknown_entities(true);
+
+    let err = from_str_with_options::<Doc>("<doc>&nbsp;</doc>", &options).unwrap_err();
+    assert!(matches!(parser_error(&err), XmlError::UnknownEntity(name) if name == "nbsp"));
+
+    let err = from_str_with_options::<Doc>(r#"<doc href="&nbsp;"/>"#, &options).unwrap_err();
+    assert!(matches!(parser_error(&err), XmlError::UnknownEntity(name) if name == "nbsp"));
+}
+
+#[test]
+fn billion_laughs_is_rejected() {
+    let input = r#"<!DOCTYPE doc [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+  <!ENTITY lol4 "&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;">
+  <!ENTITY lol5 "&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;">
+  <!ENTITY lol6 "&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;">
+  <!ENTITY lol7 "&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;">
+  <!ENTITY lol8 "&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;">
+  <!ENTITY lol9 "&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;">
+]>
+<doc>&lol9;</doc>"#;
+    let err = from_str::<Doc>(input).unwrap_err();
+    assert!(matches!(
+        parser_error(&err),
+        XmlError::LimitExceeded {
+            limit: LimitKind::EntityExpansion,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn recursive_entities_are_rejected() {
+    let input = r#"<!DOCTYPE doc [
+  <!ENTITY a "&b;">
+  <!ENTITY b "&a;">
+]>
+<doc>&a;</doc>"#;
+    let options = DeserializeOptions::new().max_entity_depth(4);
+    let err = from_str_with_options::<Doc>(input, &options).unwrap_err();
+    assert!(matches!(
+        parser_error(&err),
+        XmlError::LimitExceeded {
+            limit: LimitKind::EntityDepth,
+            max: 4
+        }
+    ));
+}
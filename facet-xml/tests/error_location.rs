@@ -0,0 +1,134 @@
+//! Tests for source locations attached to deserialization errors.
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml as xml;
+
+#[derive(Facet, Debug)]
+struct Entry {
+    id: u32,
+    #[facet(default)]
+    title: String,
+}
+
+#[derive(Facet, Debug)]
+struct Feed {
+    #[facet(xml::elements)]
+    entries: Vec<Entry>,
+}
+
+#[test]
+fn scalar_parse_error_points_at_the_text() {
+    let input = "<feed>\n  <entry>\n    <id>1</id>\n    <title>a</title>\n  </entry>\n  <entry>\n    <id>abc</id>\n  </entry>\n</feed>";
+    let err = facet_xml::from_str::<Feed>(input).unwrap_err();
+
+    let location = err.location().expect("error should carry a location");
+    assert_eq!((location.line, location.column), (7, 9));
+
+    let span = err.span().expect("error should carry a span");
+    assert_eq!(&input[span.offset as usize..span.end()], "abc");
+
+    let message = err.to_string();
+    assert!(message.starts_with("7:9: "), "got: {message}");
+    assert!(message.contains("abc"), "got: {message}");
+}
+
+#[test]
+fn syntax_error_reports_location() {
+    let input = "<feed>\n  <entry>\n    <id>1</id\n</feed>";
+    let err = facet_xml::from_str::<Feed>(input).unwrap_err();
+
+    let location = err.location().expect("error should carry a location");
+    assert_eq!(location.line, 3);
+    assert!(matches!(err.kind(), xml::DeserializeErrorKind::Parser(_)));
+}
+
+#[test]
+fn unknown_element_reports_its_own_line() {
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Config {
+        name: String,
+    }
+
+    let input = "<config>\n  <name>x</name>\n  <bogus/>\n</config>";
+    let err = facet_xml::from_str::<Config>(input).unwrap_err();
+
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((3, 3)));
+    assert!(matches!(
+        err.kind(),
+        xml::DeserializeErrorKind::UnknownElement { tag } if tag == "bogus"
+    ));
+}
+
+#[test]
+fn columns_count_characters_not_bytes() {
+    let location = facet_dom::SourceLocation::from_offset("<a>é<b>".as_bytes(), 5);
+    assert_eq!((location.line, location.column), (1, 5));
+}
+
+#[derive(Facet, Debug)]
+struct Link {
+    #[facet(xml::attribute)]
+    length: u64,
+}
+
+#[derive(Facet, Debug)]
+struct Item {
+    #[facet(xml::elements)]
+    links: Vec<Link>,
+}
+
+#[derive(Facet, Debug)]
+#[facet(rename = "feed")]
+struct Podcast {
+    #[facet(xml::elements, rename = "entry")]
+    entries: Vec<Item>,
+}
+
+#[test]
+fn attribute_error_reports_element_path() {
+    let input = r#"<feed>
+  <entry><link length="1"/></entry>
+  <entry><link length="2"/></entry>
+  <entry><link length="oops"/><link length="3"/></entry>
+</feed>"#;
+    let err = facet_xml::from_str::<Podcast>(input).unwrap_err();
+
+    let path = err.path().expect("error should carry a path");
+    assert_eq!(path.to_string(), "/feed/entry[3]/link[1]/@length");
+    assert!(err.to_string().contains("/feed/entry[3]/link[1]/@length: "));
+}
+
+#[test]
+fn scalar_error_path_includes_the_failing_element() {
+    let input = "<feed><entry><id>1</id></entry><entry><id>abc</id></entry></feed>";
+    let err = facet_xml::from_str::<Feed>(input).unwrap_err();
+
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[2]/id[1]")
+    );
+}
+
+#[test]
+fn skipped_siblings_count_towards_the_index() {
+    let input = "<feed><entry><id>1</id><extra/></entry><extra/><entry><id>x</id></entry></feed>";
+    let err = facet_xml::from_str::<Feed>(input).unwrap_err();
+
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[2]/id[1]")
+    );
+}
+
+#[test]
+fn missing_field_reports_the_incomplete_element() {
+    let input = "<feed><entry><id>1</id></entry><entry><title>t</title></entry></feed>";
+    let err = facet_xml::from_str::<Feed>(input).unwrap_err();
+
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[2]")
+    );
+}
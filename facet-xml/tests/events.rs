@@ -0,0 +1,101 @@
+//! Tests for the CData and XmlDeclaration events.
+
+use std::borrow::Cow;
+
+use facet::Facet;
+use facet_dom::{DomEvent, DomParser};
+use facet_testhelpers::test;
+use facet_xml::{self as xml, XmlParser, from_str};
+
+fn events(input: &str) -> Vec<DomEvent<'_>> {
+    let mut parser = XmlParser::new(input.as_bytes());
+    let mut events = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        events.push(event);
+    }
+    events
+}
+
+#[test]
+fn declaration_is_reported() {
+    let events = events(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><a/>"#);
+    assert_eq!(
+        events[0],
+        DomEvent::XmlDeclaration {
+            version: Cow::Borrowed("1.0"),
+            encoding: Some(Cow::Borrowed("UTF-8")),
+            standalone: Some(true),
+        }
+    );
+
+    let events = self::events("<?xml version='1.1'?><a/>");
+    assert_eq!(
+        events[0],
+        DomEvent::XmlDeclaration {
+            version: Cow::Borrowed("1.1"),
+            encoding: None,
+            standalone: None,
+        }
+    );
+}
+
+#[test]
+fn cdata_is_kept_apart_from_text() {
+    let events = events("<a>x <![CDATA[<b>&amp;</b>]]> y</a>");
+    assert_eq!(
+        &events[2..5],
+        [
+            DomEvent::Text(Cow::Borrowed("x")),
+            DomEvent::CData(Cow::Borrowed("<b>&amp;</b>")),
+            DomEvent::Text(Cow::Borrowed("y")),
+        ]
+    );
+}
+
+#[test]
+fn cdata_deserializes_like_text() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Script {
+        #[facet(xml::attribute)]
+        lang: String,
+        #[facet(xml::text)]
+        code: String,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Page {
+        title: String,
+        script: Script,
+    }
+
+    let page: Page = from_str(
+        r#"<?xml version="1.0"?>
+<page>
+  <title><![CDATA[Tom & Jerry]]></title>
+  <script lang="js"><![CDATA[if (a < b) { go(); }]]></script>
+</page>"#,
+    )
+    .unwrap();
+
+    assert_eq!(page.title, "Tom & Jerry");
+    assert_eq!(page.script.code, "if (a < b) { go(); }");
+}
+
+#[test]
+fn declaration_is_skipped_before_any_root() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Shape {
+        Circle {
+            #[facet(xml::attribute)]
+            r: u32,
+        },
+        Square {
+            #[facet(xml::attribute)]
+            side: u32,
+        },
+    }
+
+    let shape: Shape = from_str(r#"<?xml version="1.0"?><square side="3"/>"#).unwrap();
+    assert_eq!(shape, Shape::Square { side: 3 });
+}
@@ -0,0 +1,146 @@
+//! Tests for lazily iterating over repeated elements.
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{
+    self as xml, DeserializeOptions, LimitKind, NameMatching, NamespaceMatching, ParserLimits,
+    XmlError, iter_elements, iter_elements_with_options,
+};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    #[facet(xml::attribute)]
+    id: u32,
+    title: String,
+}
+
+#[test]
+fn yields_each_matching_element() {
+    let input = br#"<feed>
+  <title>Feed title</title>
+  <entry id="1"><title>one</title></entry>
+  <!-- a comment -->
+  <entry id="2"><title>two</title></entry>
+  <updated>2024-01-01</updated>
+  <entry id="3"><title>three</title></entry>
+</feed>"#;
+
+    let entries: Vec<Entry> = iter_elements(&input[..], "entry")
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(entries[1].title, "two");
+}
+
+#[test]
+fn finds_nested_elements() {
+    let input = br#"<rss><channel><title>c</title>
+  <item id="1"><title>a</title></item>
+  <item id="2"><title>b</title></item>
+</channel></rss>"#;
+
+    let titles: Vec<String> = iter_elements::<Entry, _>(&input[..], "item")
+        .map(|item| item.unwrap().title)
+        .collect();
+
+    assert_eq!(titles, ["a", "b"]);
+}
+
+#[test]
+fn empty_when_nothing_matches() {
+    let mut iter = iter_elements::<Entry, _>(&b"<feed><title>t</title></feed>"[..], "entry");
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn stops_after_an_error() {
+    let input = br#"<feed>
+  <entry id="1"><title>one</title></entry>
+  <entry id="two"><title>two</title></entry>
+  <entry id="3"><title>three</title></entry>
+</feed>"#;
+
+    let mut iter = iter_elements::<Entry, _>(&input[..], "entry");
+    assert_eq!(iter.next().unwrap().unwrap().id, 1);
+
+    let err = iter.next().unwrap().unwrap_err();
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[2]/@id")
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn options_and_limits_apply() {
+    let input = br#"<feed>
+  <Entry ID="1"><Title>one</Title></Entry>
+  <Entry ID="2"><Title>two</Title></Entry>
+</feed>"#;
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let ids: Vec<u32> = iter_elements_with_options::<Entry, _>(&input[..], "Entry", &options)
+        .map(|entry| entry.unwrap().id)
+        .collect();
+    assert_eq!(ids, [1, 2]);
+
+    // Limits count across the whole stream, not per item
+    let options = options.limits(ParserLimits::new().max_elements(4));
+    let mut iter = iter_elements_with_options::<Entry, _>(&input[..], "Entry", &options);
+    assert_eq!(iter.next().unwrap().unwrap().id, 1);
+    let err = iter.next().unwrap().unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        xml::DeserializeErrorKind::Parser(XmlError::LimitExceeded {
+            limit: LimitKind::Elements,
+            ..
+        })
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn names_follow_name_matching() {
+    let input = br#"<feed><ENTRY id="1"><title>one</title></ENTRY><entry id="2"><title>two</title></entry></feed>"#;
+
+    let ids: Vec<u32> = iter_elements::<Entry, _>(&input[..], "entry")
+        .map(|entry| entry.unwrap().id)
+        .collect();
+    assert_eq!(ids, [2]);
+
+    let options = DeserializeOptions::new().name_matching(NameMatching::IgnoreCase);
+    let ids: Vec<u32> = iter_elements_with_options::<Entry, _>(&input[..], "entry", &options)
+        .map(|entry| entry.unwrap().id)
+        .collect();
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn namespaces_follow_namespace_matching() {
+    const ATOM: &str = "http://www.w3.org/2005/Atom";
+    let input = br#"<feed xmlns:atom="http://www.w3.org/2005/Atom">
+  <entry id="1"><title>plain</title></entry>
+  <atom:entry id="2"><atom:title>atom</atom:title></atom:entry>
+</feed>"#;
+
+    let ids = |options: &DeserializeOptions, namespace: Option<&str>| -> Vec<u32> {
+        let iter = iter_elements_with_options::<Entry, _>(&input[..], "entry", options);
+        let iter = match namespace {
+            Some(uri) => iter.namespace(uri),
+            None => iter,
+        };
+        iter.map(|entry| entry.unwrap().id).collect()
+    };
+
+    let default = DeserializeOptions::new();
+    assert_eq!(ids(&default, None), [1, 2]);
+    assert_eq!(ids(&default, Some(ATOM)), [2]);
+
+    let exact = DeserializeOptions::new().namespace_matching(NamespaceMatching::Exact);
+    assert_eq!(ids(&exact, None), [1]);
+
+    let ignore = DeserializeOptions::new().namespace_matching(NamespaceMatching::Ignore);
+    assert_eq!(ids(&ignore, Some(ATOM)), [1, 2]);
+}
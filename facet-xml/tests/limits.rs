@@ -0,0 +1,130 @@
+//! Tests for parser resource limits.
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{
+    self as xml, DeserializeError, DeserializeErrorKind, DeserializeOptions, LimitKind,
+    ParserLimits, XmlError, from_str_with_options,
+};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Node {
+    #[facet(xml::attribute, default)]
+    id: Option<String>,
+    #[facet(xml::elements, default)]
+    children: Vec<Node>,
+    #[facet(xml::text, default)]
+    text: String,
+}
+
+fn parse(input: &str, limits: ParserLimits) -> Result<Node, DeserializeError<XmlError>> {
+    from_str_with_options(input, &DeserializeOptions::new().limits(limits))
+}
+
+fn exceeded(err: &DeserializeError<XmlError>) -> Option<(LimitKind, usize)> {
+    match err.kind() {
+        DeserializeErrorKind::Parser(XmlError::LimitExceeded { limit, max }) => {
+            Some((*limit, *max))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn no_limits_by_default() {
+    let deep = "<node>".repeat(40) + &"</node>".repeat(40);
+    parse(&deep, ParserLimits::default()).unwrap();
+}
+
+#[test]
+fn depth_limit() {
+    let input = "<node><node><node></node></node></node>";
+    parse(input, ParserLimits::new().max_depth(3)).unwrap();
+
+    let err = parse(input, ParserLimits::new().max_depth(2)).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::Depth, 2)));
+    assert!(
+        err.to_string().contains("nesting depth limit exceeded"),
+        "got: {err}"
+    );
+}
+
+#[test]
+fn attribute_limit_counts_namespace_declarations() {
+    let input = r#"<node xmlns:a="urn:a" xmlns:b="urn:b" id="1"/>"#;
+    parse(input, ParserLimits::new().max_attributes(3)).unwrap();
+
+    let err = parse(input, ParserLimits::new().max_attributes(2)).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::Attributes, 2)));
+}
+
+#[test]
+fn text_size_limit_applies_after_entity_expansion() {
+    let input = "<node>&lt;&lt;&lt;&lt;</node>";
+    parse(input, ParserLimits::new().max_text_size(4)).unwrap();
+
+    let err = parse(input, ParserLimits::new().max_text_size(3)).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::TextSize, 3)));
+}
+
+#[test]
+fn text_size_limit_applies_to_cdata() {
+    let err = parse(
+        "<node><![CDATA[0123456789]]></node>",
+        ParserLimits::new().max_text_size(8),
+    )
+    .unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::TextSize, 8)));
+}
+
+#[test]
+fn element_limit() {
+    let input = "<node><node/><node/><node/></node>";
+    parse(input, ParserLimits::new().max_elements(4)).unwrap();
+
+    let err = parse(input, ParserLimits::new().max_elements(3)).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::Elements, 3)));
+}
+
+#[test]
+fn event_limit() {
+    // NodeStart, ChildrenStart, Text, ChildrenEnd, NodeEnd
+    let input = "<node>hi</node>";
+    parse(input, ParserLimits::new().max_events(5)).unwrap();
+
+    let err = parse(input, ParserLimits::new().max_events(4)).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::Events, 4)));
+}
+
+#[test]
+fn limit_errors_carry_a_location() {
+    let input = "<node>\n  <node>\n    <node/>\n  </node>\n</node>";
+    let err = parse(input, ParserLimits::new().max_depth(2)).unwrap_err();
+    assert_eq!(err.location().map(|l| (l.line, l.column)), Some((3, 5)));
+}
+
+#[test]
+fn entity_references_count_even_when_empty() {
+    // Seven levels of ten references to an empty entity: 10^7 expansions
+    // that produce no text at all
+    let mut doctype = String::from("<!DOCTYPE node [<!ENTITY e0 \"\">");
+    for level in 1..=7 {
+        let refs = format!("&e{};", level - 1).repeat(10);
+        doctype.push_str(&format!("<!ENTITY e{level} \"{refs}\">"));
+    }
+    doctype.push_str("]>");
+
+    let input = format!("{doctype}<node>&e7;</node>");
+    let options = DeserializeOptions::new().max_entity_expansion(1 << 16);
+    let err = from_str_with_options::<Node>(&input, &options).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::EntityExpansion, 1 << 16)));
+
+    let input = format!("{doctype}<node id=\"&e7;\"/>");
+    let err = from_str_with_options::<Node>(&input, &options).unwrap_err();
+    assert_eq!(exceeded(&err), Some((LimitKind::EntityExpansion, 1 << 16)));
+
+    // A few references still expand
+    let input = format!("{doctype}<node>a&e2;b</node>");
+    let node = from_str_with_options::<Node>(&input, &options).unwrap();
+    assert_eq!(node.text, "ab");
+}
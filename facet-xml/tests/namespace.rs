@@ -1631,3 +1631,98 @@ fn test_serialize_options_preserve_entities() {
         "With preserve_entities, &amp; should be preserved: {xml_preserved}"
     );
 }
+
+// ============================================================================
+// Strict namespace mode
+// ============================================================================
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "Envelope")]
+struct Envelope {
+    #[facet(xml::attribute, default)]
+    id: Option<String>,
+    #[facet(rename = "Body")]
+    body: String,
+}
+
+fn strict<T: Facet<'static>>(
+    xml_str: &str,
+) -> Result<T, facet_xml::DeserializeError<facet_xml::XmlError>> {
+    let options = facet_xml::DeserializeOptions::new().strict_namespaces(true);
+    facet_xml::from_str_with_options(xml_str, &options)
+}
+
+fn strict_error(xml_str: &str) -> (facet_xml::XmlError, (usize, usize)) {
+    let err = strict::<Envelope>(xml_str).unwrap_err();
+    let location = err.location().map(|l| (l.line, l.column)).unwrap();
+    match err.kind() {
+        facet_xml::DeserializeErrorKind::Parser(e) => (e.clone(), location),
+        other => panic!("expected a parser error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_undeclared_prefix_is_ignored_by_default() {
+    let xml = "<soap:Envelope><soap:Body>ok</soap:Body></soap:Envelope>";
+    let parsed: Envelope = from_str(xml).unwrap();
+    assert_eq!(parsed.body, "ok");
+}
+
+#[test]
+fn test_strict_accepts_declared_prefixes() {
+    let xml = r#"<soap:Envelope xmlns:soap="urn:soap" xml:lang="en"><soap:Body>ok</soap:Body></soap:Envelope>"#;
+    let parsed: Envelope = strict(xml).unwrap();
+    assert_eq!(parsed.body, "ok");
+}
+
+#[test]
+fn test_strict_rejects_undeclared_element_prefix() {
+    let xml = "<Envelope>\n  <soap:Body>ok</soap:Body>\n</Envelope>";
+    let (err, location) = strict_error(xml);
+    assert!(
+        matches!(&err, facet_xml::XmlError::UnboundPrefix { prefix } if prefix == "soap"),
+        "got {err:?}"
+    );
+    assert_eq!(location, (2, 3));
+}
+
+#[test]
+fn test_strict_rejects_undeclared_attribute_prefix() {
+    let xml = r#"<Envelope wsu:id="1"><Body>ok</Body></Envelope>"#;
+    let (err, _) = strict_error(xml);
+    assert!(
+        matches!(&err, facet_xml::XmlError::UnboundPrefix { prefix } if prefix == "wsu"),
+        "got {err:?}"
+    );
+}
+
+#[test]
+fn test_strict_reports_mismatched_end_tag() {
+    let xml = "<Envelope>\n  <Body>ok</Bdy>\n</Envelope>";
+    let (err, location) = strict_error(xml);
+    assert!(
+        matches!(
+            &err,
+            facet_xml::XmlError::UnbalancedTags { expected: Some(e), found: Some(f) }
+                if e == "Body" && f == "Bdy"
+        ),
+        "got {err:?}"
+    );
+    assert_eq!(location.0, 2);
+    assert_eq!(
+        err.to_string(),
+        "Unbalanced XML tags: expected </Body>, found </Bdy>"
+    );
+}
+
+#[test]
+fn test_strict_reports_unclosed_element() {
+    let (err, _) = strict_error("<Envelope><Body>ok</Body>");
+    assert!(
+        matches!(
+            &err,
+            facet_xml::XmlError::UnbalancedTags { expected: Some(e), found: None } if e == "Envelope"
+        ),
+        "got {err:?}"
+    );
+}
@@ -0,0 +1,179 @@
+//! Tests for choosing namespace prefixes and hoisting namespace declarations.
+
+use facet::Facet;
+use facet_xml::{self as xml, RootElement, SerializeOptions, XmlDocumentWriter};
+
+const SOAP: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+const ORDERS: &str = "urn:example:orders";
+const AUDIT: &str = "urn:example:audit";
+
+#[derive(Facet, Debug, PartialEq)]
+struct Line {
+    #[facet(xml::attribute, xml::ns = "urn:example:audit")]
+    checked: bool,
+    #[facet(xml::ns = "urn:example:orders")]
+    sku: String,
+    #[facet(xml::ns = "urn:example:orders")]
+    quantity: u32,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Order {
+    #[facet(xml::elements)]
+    lines: Vec<Line>,
+}
+
+fn order() -> Order {
+    Order {
+        lines: vec![
+            Line {
+                checked: true,
+                sku: "A-1".into(),
+                quantity: 2,
+            },
+            Line {
+                checked: false,
+                sku: "B-2".into(),
+                quantity: 1,
+            },
+        ],
+    }
+}
+
+#[test]
+fn prefixes_from_options() {
+    let options = SerializeOptions::new().namespace_prefixes([(ORDERS, "ord"), (AUDIT, "au")]);
+    let out = xml::to_string_with_options(&order(), &options).unwrap();
+    assert!(out.contains(&format!(r#"<line xmlns:au="{AUDIT}" au:checked="true">"#)));
+    assert!(out.contains(&format!(r#"<ord:sku xmlns:ord="{ORDERS}">A-1</ord:sku>"#)));
+    assert!(!out.contains("ns0"));
+    assert_eq!(xml::from_str::<Order>(&out).unwrap(), order());
+}
+
+#[test]
+fn field_prefix_attribute() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Invoice {
+        #[facet(xml::ns = "urn:example:orders", xml::prefix = "o")]
+        number: u32,
+        #[facet(xml::ns = "urn:example:audit", xml::prefix = "o")]
+        auditor: String,
+    }
+
+    let invoice = Invoice {
+        number: 7,
+        auditor: "kim".into(),
+    };
+    let out = xml::to_string(&invoice).unwrap();
+    // The second namespace cannot take a prefix that is already in use
+    assert_eq!(
+        out,
+        format!(
+            r#"<invoice><o:number xmlns:o="{ORDERS}">7</o:number><ns0:auditor xmlns:ns0="{AUDIT}">kim</ns0:auditor></invoice>"#
+        )
+    );
+    assert_eq!(xml::from_str::<Invoice>(&out).unwrap(), invoice);
+}
+
+#[test]
+fn container_prefix_attribute() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(
+        rename = "Envelope",
+        xml::ns_all = "http://schemas.xmlsoap.org/soap/envelope/",
+        xml::prefix = "soap"
+    )]
+    struct Envelope {
+        #[facet(rename = "Body")]
+        body: String,
+    }
+
+    let envelope = Envelope { body: "hi".into() };
+    let out =
+        xml::to_string_with_options(&envelope, &SerializeOptions::new().hoist_namespaces(true))
+            .unwrap();
+    assert_eq!(
+        out,
+        format!(r#"<soap:Envelope xmlns:soap="{SOAP}"><soap:Body>hi</soap:Body></soap:Envelope>"#)
+    );
+    assert_eq!(xml::from_str::<Envelope>(&out).unwrap(), envelope);
+}
+
+#[test]
+fn hoisted_declarations_appear_once() {
+    let options = SerializeOptions::new()
+        .namespace_prefixes([(ORDERS, "ord"), (AUDIT, "au")])
+        .hoist_namespaces(true);
+    let out = xml::to_string_with_options(&order(), &options).unwrap();
+    assert_eq!(
+        out,
+        format!(
+            concat!(
+                r#"<order xmlns:au="{AUDIT}" xmlns:ord="{ORDERS}">"#,
+                r#"<line au:checked="true"><ord:sku>A-1</ord:sku><ord:quantity>2</ord:quantity></line>"#,
+                r#"<line au:checked="false"><ord:sku>B-2</ord:sku><ord:quantity>1</ord:quantity></line>"#,
+                "</order>"
+            ),
+            ORDERS = ORDERS,
+            AUDIT = AUDIT
+        )
+    );
+    assert_eq!(xml::from_str::<Order>(&out).unwrap(), order());
+}
+
+#[test]
+fn hoisting_works_with_writers() {
+    let big = Order {
+        lines: (0..2_000)
+            .map(|i| Line {
+                checked: i % 3 == 0,
+                sku: format!("SKU-{i}"),
+                quantity: i,
+            })
+            .collect(),
+    };
+    let options = SerializeOptions::new().hoist_namespaces(true);
+    let mut writer = Chunks::default();
+    xml::to_writer_with_options(&mut writer, &big, &options).unwrap();
+    let out = String::from_utf8(writer.bytes).unwrap();
+
+    // Output is still written as it is produced
+    assert!(writer.writes > 1);
+    assert_eq!(out.matches("xmlns:").count(), 2);
+    assert_eq!(xml::from_str::<Order>(&out).unwrap(), big);
+}
+
+#[test]
+fn document_writer_declares_unlisted_namespaces_locally() {
+    let options = SerializeOptions::new()
+        .namespace_prefixes([(ORDERS, "ord"), (AUDIT, "au")])
+        .hoist_namespaces(true);
+    let root = RootElement::new("order").namespace("ord", ORDERS);
+    let mut doc = XmlDocumentWriter::open_with_options(Vec::new(), &root, options).unwrap();
+    for line in order().lines {
+        doc.write_item(&line).unwrap();
+    }
+    let out = String::from_utf8(doc.close().unwrap()).unwrap();
+
+    assert_eq!(out.matches(&format!("xmlns:ord=\"{ORDERS}\"")).count(), 1);
+    assert_eq!(out.matches(&format!("xmlns:au=\"{AUDIT}\"")).count(), 2);
+}
+
+/// A writer recording how many writes it received.
+#[derive(Default)]
+struct Chunks {
+    bytes: Vec<u8>,
+    writes: usize,
+}
+
+impl std::io::Write for Chunks {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Tests for deserializing from `std::io::Read` sources.
+
+use std::io::{self, Read};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{self as xml, DeserializeErrorKind, RawMarkup, from_reader};
+
+/// A reader that hands out at most a few bytes per call, to exercise buffer refills.
+struct Trickle<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    #[facet(xml::attribute)]
+    id: u32,
+    title: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Feed {
+    #[facet(xml::elements)]
+    entries: Vec<Entry>,
+}
+
+const FEED: &str = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry id="1"><title>R&amp;D</title></entry>
+  <entry id="2"><title><![CDATA[<second>]]></title></entry>
+</feed>"#;
+
+#[test]
+fn reads_from_a_trickling_reader() {
+    let feed: Feed = from_reader(Trickle {
+        data: FEED.as_bytes(),
+        chunk: 3,
+    })
+    .unwrap();
+
+    assert_eq!(
+        feed,
+        Feed {
+            entries: vec![
+                Entry {
+                    id: 1,
+                    title: "R&D".into(),
+                },
+                Entry {
+                    id: 2,
+                    title: "<second>".into(),
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn matches_slice_deserialization() {
+    let from_slice: Feed = xml::from_slice(FEED.as_bytes()).unwrap();
+    let from_reader: Feed = from_reader(FEED.as_bytes()).unwrap();
+    assert_eq!(from_slice, from_reader);
+}
+
+#[test]
+fn errors_report_byte_offsets() {
+    let input = "<feed><entry id=\"x\"><title>t</title></entry></feed>";
+    let err = from_reader::<Feed, _>(input.as_bytes()).unwrap_err();
+
+    assert!(err.location().is_none());
+    assert!(err.span().is_some());
+    assert_eq!(
+        err.path().map(ToString::to_string).as_deref(),
+        Some("/feed/entry[1]/@id")
+    );
+}
+
+#[test]
+fn io_errors_are_reported() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("connection reset"))
+        }
+    }
+
+    let err = from_reader::<Feed, _>(Failing).unwrap_err();
+    assert!(matches!(err.kind(), DeserializeErrorKind::Parser(_)));
+    assert!(err.to_string().contains("connection reset"), "got: {err}");
+}
+
+#[test]
+fn raw_markup_is_unsupported() {
+    #[derive(Facet, Debug)]
+    struct Document {
+        body: RawMarkup,
+    }
+
+    let err =
+        from_reader::<Document, _>(&b"<document><body><p/></body></document>"[..]).unwrap_err();
+    assert!(matches!(err.kind(), DeserializeErrorKind::Unsupported(_)));
+}
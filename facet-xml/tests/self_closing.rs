@@ -0,0 +1,97 @@
+//! Tests for writing empty elements as `<tag/>` with SerializeOptions::self_close_empty.
+
+use facet::Facet;
+use facet_xml::{self as xml, SerializeOptions};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Link {
+    #[facet(xml::attribute)]
+    rel: String,
+    #[facet(xml::attribute)]
+    href: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Entry {
+    title: String,
+    #[facet(xml::elements)]
+    links: Vec<Link>,
+    summary: Option<String>,
+    content: String,
+}
+
+fn entry() -> Entry {
+    Entry {
+        title: "Hello".into(),
+        links: vec![
+            Link {
+                rel: "alternate".into(),
+                href: "/hello".into(),
+            },
+            Link {
+                rel: "edit".into(),
+                href: "/hello/edit".into(),
+            },
+        ],
+        summary: None,
+        content: String::new(),
+    }
+}
+
+#[test]
+fn end_tags_by_default() {
+    let out = xml::to_string(&entry()).unwrap();
+    assert!(out.contains(r#"<link rel="alternate" href="/hello"></link>"#));
+}
+
+#[test]
+fn empty_elements_self_close() {
+    let options = SerializeOptions::new().self_close_empty(true);
+    let out = xml::to_string_with_options(&entry(), &options).unwrap();
+    assert_eq!(
+        out,
+        r#"<entry><title>Hello</title><link rel="alternate" href="/hello"/><link rel="edit" href="/hello/edit"/><content/></entry>"#
+    );
+    assert_eq!(xml::from_str::<Entry>(&out).unwrap(), entry());
+}
+
+#[test]
+fn elements_with_children_keep_end_tags() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Group {
+        #[facet(xml::attribute)]
+        id: String,
+        #[facet(xml::elements)]
+        items: Vec<Group>,
+    }
+
+    let group = Group {
+        id: "outer".into(),
+        items: vec![Group {
+            id: "inner".into(),
+            items: vec![],
+        }],
+    };
+    let options = SerializeOptions::new().self_close_empty(true);
+    let out = xml::to_string_with_options(&group, &options).unwrap();
+    assert_eq!(out, r#"<group id="outer"><group id="inner"/></group>"#);
+    assert_eq!(xml::from_str::<Group>(&out).unwrap(), group);
+}
+
+#[test]
+fn void_elements_only() {
+    let options = SerializeOptions::new().void_elements(["link"]);
+    let out = xml::to_string_with_options(&entry(), &options).unwrap();
+    assert_eq!(
+        out,
+        r#"<entry><title>Hello</title><link rel="alternate" href="/hello"/><link rel="edit" href="/hello/edit"/><content></content></entry>"#
+    );
+}
+
+#[test]
+fn pretty_output() {
+    let options = SerializeOptions::new().self_close_empty(true).pretty();
+    let out = xml::to_string_with_options(&entry(), &options).unwrap();
+    assert!(out.contains("  <link rel=\"alternate\" href=\"/hello\"/>\n  <link"));
+    assert_eq!(xml::from_str::<Entry>(&out).unwrap(), entry());
+}
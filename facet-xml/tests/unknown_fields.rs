@@ -0,0 +1,101 @@
+//! Tests for rejecting unknown attributes and unknown elements separately.
+
+use facet::Facet;
+use facet_xml::{self as xml, DeserializeOptions};
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "item", xml::deny_unknown_elements)]
+struct StrictElements {
+    #[facet(xml::attribute)]
+    id: String,
+    name: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "item", xml::deny_unknown_attributes)]
+struct StrictAttributes {
+    #[facet(xml::attribute)]
+    id: String,
+    name: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "item")]
+struct Lenient {
+    #[facet(xml::attribute)]
+    id: String,
+    name: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[facet(rename = "item", deny_unknown_fields)]
+struct Strict {
+    #[facet(xml::attribute)]
+    id: String,
+    name: String,
+}
+
+const EXTRA_ATTRIBUTES: &str =
+    r#"<item id="1" xml:lang="en" data-vendor="x" xmlns:old="urn:old"><name>a</name></item>"#;
+const EXTRA_ELEMENT: &str = r#"<item id="1"><name>a</name><extra/></item>"#;
+
+#[test]
+fn deny_unknown_elements_tolerates_attributes() {
+    let item: StrictElements = xml::from_str(EXTRA_ATTRIBUTES).unwrap();
+    assert_eq!(item.name, "a");
+
+    let err = xml::from_str::<StrictElements>(EXTRA_ELEMENT).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        xml::DeserializeErrorKind::UnknownElement { tag } if tag == "extra"
+    ));
+}
+
+#[test]
+fn deny_unknown_attributes_tolerates_elements() {
+    let item: StrictAttributes = xml::from_str(EXTRA_ELEMENT).unwrap();
+    assert_eq!(item.name, "a");
+
+    let err = xml::from_str::<StrictAttributes>(EXTRA_ATTRIBUTES).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        xml::DeserializeErrorKind::UnknownAttribute { name } if name == "lang"
+    ));
+}
+
+#[test]
+fn deny_unknown_fields_rejects_both() {
+    assert!(xml::from_str::<Strict>(EXTRA_ATTRIBUTES).is_err());
+    assert!(xml::from_str::<Strict>(EXTRA_ELEMENT).is_err());
+}
+
+#[test]
+fn options_override_the_types() {
+    let lenient = DeserializeOptions::new()
+        .deny_unknown_attributes(false)
+        .deny_unknown_elements(false);
+    let item: Strict = xml::from_str_with_options(EXTRA_ATTRIBUTES, &lenient).unwrap();
+    assert_eq!(item.id, "1");
+    let item: Strict = xml::from_str_with_options(EXTRA_ELEMENT, &lenient).unwrap();
+    assert_eq!(item.id, "1");
+
+    let strict = DeserializeOptions::new().deny_unknown_elements(true);
+    assert!(xml::from_str_with_options::<Lenient>(EXTRA_ELEMENT, &strict).is_err());
+    // Attributes still follow the type
+    let item: Lenient = xml::from_str_with_options(EXTRA_ATTRIBUTES, &strict).unwrap();
+    assert_eq!(item.id, "1");
+}
+
+#[test]
+fn options_apply_to_nested_types() {
+    #[derive(Facet, Debug)]
+    struct List {
+        #[facet(xml::elements)]
+        items: Vec<Lenient>,
+    }
+
+    let input = r#"<list><item id="1"><name>a</name></item><item id="2" rel="x"><name>b</name></item></list>"#;
+    let strict = DeserializeOptions::new().deny_unknown_attributes(true);
+    let err = xml::from_str_with_options::<List>(input, &strict).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "/list/item[2]/@rel");
+}
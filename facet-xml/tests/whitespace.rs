@@ -0,0 +1,111 @@
+//! Tests for whitespace handling in text content.
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_xml::{self as xml, DeserializeOptions, from_str, from_str_with_options};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Pre {
+    #[facet(xml::text)]
+    content: String,
+}
+
+fn preserving() -> DeserializeOptions {
+    DeserializeOptions::new().preserve_whitespace(true)
+}
+
+#[test]
+fn text_is_trimmed_by_default() {
+    let pre: Pre = from_str("<pre>\n  indented\n</pre>").unwrap();
+    assert_eq!(pre.content, "indented");
+}
+
+#[test]
+fn entity_references_keep_surrounding_spaces() {
+    let pre: Pre = from_str("<pre> R &amp; D </pre>").unwrap();
+    assert_eq!(pre.content, "R & D");
+}
+
+#[test]
+fn preserve_option_keeps_text_verbatim() {
+    let pre: Pre =
+        from_str_with_options("<pre>\n  indented &lt;tag&gt;\n</pre>", &preserving()).unwrap();
+    assert_eq!(pre.content, "\n  indented <tag>\n");
+}
+
+#[test]
+fn xml_space_preserve_is_honoured() {
+    let pre: Pre = from_str(r#"<pre xml:space="preserve">  two  spaces  </pre>"#).unwrap();
+    assert_eq!(pre.content, "  two  spaces  ");
+}
+
+#[test]
+fn xml_space_is_inherited_and_can_be_reset() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Doc {
+        kept: Pre,
+        trimmed: Pre,
+    }
+
+    let input = r#"<doc xml:space="preserve"><kept> a </kept><trimmed xml:space="default"> b </trimmed></doc>"#;
+    let doc: Doc = from_str(input).unwrap();
+    assert_eq!(doc.kept.content, " a ");
+    assert_eq!(doc.trimmed.content, "b");
+}
+
+#[test]
+fn xml_space_default_uses_the_configured_mode() {
+    let pre: Pre =
+        from_str_with_options(r#"<pre xml:space="default"> x </pre>"#, &preserving()).unwrap();
+    assert_eq!(pre.content, " x ");
+}
+
+#[test]
+fn whitespace_between_fields_does_not_break_structs() {
+    #[derive(Facet, Debug, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    let input = "<person>\n  <name>Alice</name>\n  <age>30</age>\n</person>\n";
+    let person: Person = from_str_with_options(input, &preserving()).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Alice".into(),
+            age: 30
+        }
+    );
+}
+
+#[test]
+fn mixed_content_round_trips() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Inline {
+        #[facet(xml::text)]
+        Text(String),
+        #[facet(rename = "b")]
+        Bold(String),
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(rename = "p")]
+    struct Paragraph {
+        #[facet(flatten)]
+        children: Vec<Inline>,
+    }
+
+    let input = "<p>Hello <b>big</b> world</p>";
+    let p: Paragraph = from_str_with_options(input, &preserving()).unwrap();
+    assert_eq!(
+        p.children,
+        [
+            Inline::Text("Hello ".into()),
+            Inline::Bold("big".into()),
+            Inline::Text(" world".into()),
+        ]
+    );
+    assert_eq!(xml::to_string(&p).unwrap(), input);
+}
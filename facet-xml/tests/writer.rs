@@ -0,0 +1,100 @@
+//! Tests for serializing to io::Write and fmt::Write sinks.
+
+use std::io;
+
+use facet::Facet;
+use facet_xml::{self as xml, SerializeOptions};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Item {
+    #[facet(xml::attribute)]
+    id: u32,
+    name: String,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Catalog {
+    #[facet(xml::elements)]
+    items: Vec<Item>,
+}
+
+fn catalog(len: u32) -> Catalog {
+    Catalog {
+        items: (0..len)
+            .map(|id| Item {
+                id,
+                name: format!("item é {id}"),
+            })
+            .collect(),
+    }
+}
+
+/// Records the size of each write.
+#[derive(Default)]
+struct Chunks {
+    data: Vec<u8>,
+    writes: usize,
+}
+
+impl io::Write for Chunks {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.extend_from_slice(buf);
+        self.writes += 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Fails every write.
+struct Broken;
+
+impl io::Write for Broken {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn matches_to_vec() {
+    let value = catalog(3);
+    let mut out = Vec::new();
+    xml::to_writer(&mut out, &value).unwrap();
+    assert_eq!(out, xml::to_vec(&value).unwrap());
+}
+
+#[test]
+fn large_output_is_written_incrementally() {
+    let value = catalog(2_000);
+    let mut sink = Chunks::default();
+    xml::to_writer_with_options(&mut sink, &value, &SerializeOptions::new().pretty()).unwrap();
+
+    assert!(sink.writes > 1);
+    let expected = xml::to_string_pretty(&value).unwrap();
+    assert_eq!(String::from_utf8(sink.data).unwrap(), expected);
+}
+
+#[test]
+fn io_errors_are_reported() {
+    let err = xml::to_writer(&mut Broken, &catalog(1)).unwrap_err();
+    let xml::SerializeError::Backend(err) = err else {
+        panic!("expected a backend error, got {err:?}");
+    };
+    assert_eq!(err.io_error().unwrap().kind(), io::ErrorKind::BrokenPipe);
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn writes_to_fmt_sinks() {
+    let value = catalog(2_000);
+    let mut out = String::new();
+    xml::to_fmt_writer(&mut out, &value).unwrap();
+    assert_eq!(out, xml::to_string(&value).unwrap());
+    assert_eq!(xml::from_str::<Catalog>(&out).unwrap(), value);
+}